@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::sync::Mutex;
 use std::collections::HashMap;
+use crate::jobs::JobManager;
 use crate::models::kv::{RemoteConnection, LocalFolder};
 use crate::persistence::Database;
 
@@ -8,6 +9,8 @@ pub struct AppState {
     pub db: Mutex<Database>,
     pub folders: Mutex<HashMap<i64, LocalFolder>>,
     pub remote_connections: Mutex<Vec<RemoteConnection>>,
+    pub offline: Mutex<bool>,
+    pub jobs: JobManager,
 }
 
 impl AppState {
@@ -38,11 +41,17 @@ impl AppState {
             }
         };
 
-        let remote_connections = match db.get_remote_connections() {
-            Ok(connections) => connections,
-            Err(e) => {
-                eprintln!("Failed to load remote connections: {}", e);
-                Vec::new()
+        let vault_configured = db.is_vault_configured().unwrap_or(false);
+
+        let remote_connections = if vault_configured {
+            Vec::new()
+        } else {
+            match db.get_remote_connections() {
+                Ok(connections) => connections,
+                Err(e) => {
+                    eprintln!("Failed to load remote connections: {}", e);
+                    Vec::new()
+                }
             }
         };
 
@@ -50,6 +59,8 @@ impl AppState {
             db: Mutex::new(db),
             folders: Mutex::new(folders),
             remote_connections: Mutex::new(remote_connections),
+            offline: Mutex::new(false),
+            jobs: JobManager::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,173 @@
+use std::fs;
+use std::path::PathBuf;
+use reqwest::Client;
+use serde::Deserialize;
+use tauri::api::path::{config_dir, home_dir};
+
+use crate::models::kv::TokenPermissions;
+
+const WRANGLER_OAUTH_CLIENT_ID: &str = "54d11594-84e4-41aa-b438-e81b8fa78ee7";
+const WRANGLER_OAUTH_TOKEN_URL: &str = "https://dash.cloudflare.com/oauth2/token";
+
+pub struct WranglerLogin {
+    pub oauth_token: String,
+    pub refresh_token: Option<String>,
+    pub expiration_time: Option<chrono::DateTime<chrono::Utc>>,
+    pub scopes: Vec<String>,
+}
+
+pub struct EnvironmentCredentials {
+    pub api_token: String,
+    pub account_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OAuthTokenResponse {
+    access_token: String,
+    expires_in: i64,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+fn wrangler_config_path() -> Option<PathBuf> {
+    let legacy_dir = home_dir()?.join(".wrangler");
+
+    let config_dir = if legacy_dir.is_dir() {
+        legacy_dir
+    } else if cfg!(target_os = "macos") {
+        home_dir()?.join("Library").join("Preferences").join(".wrangler")
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(config_dir)?
+            .join(".wrangler")
+    };
+
+    Some(config_dir.join("config").join("default.toml"))
+}
+
+fn read_wrangler_login() -> Option<WranglerLogin> {
+    let content = fs::read_to_string(wrangler_config_path()?).ok()?;
+    let config: toml::Table = content.parse().ok()?;
+
+    let oauth_token = config.get("oauth_token")?.as_str()?.to_string();
+    let refresh_token = config.get("refresh_token").and_then(|t| t.as_str()).map(|t| t.to_string());
+    let expiration_time = config.get("expiration_time")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&chrono::Utc));
+    let scopes = config.get("scopes")
+        .and_then(|s| s.as_array())
+        .map(|s| s.iter().filter_map(|v| v.as_str().map(|v| v.to_string())).collect())
+        .unwrap_or_default();
+
+    Some(WranglerLogin {
+        oauth_token,
+        refresh_token,
+        expiration_time,
+        scopes,
+    })
+}
+
+fn save_wrangler_login(login: &WranglerLogin) -> Result<(), String> {
+    let path = wrangler_config_path().ok_or_else(|| "Wrangler config directory not found".to_string())?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read Wrangler config: {}", e))?;
+    let mut config: toml::Table = content.parse()
+        .map_err(|e| format!("Failed to parse Wrangler config: {}", e))?;
+
+    config.insert("oauth_token".to_string(), login.oauth_token.clone().into());
+    if let Some(refresh_token) = &login.refresh_token {
+        config.insert("refresh_token".to_string(), refresh_token.clone().into());
+    }
+    if let Some(expiration_time) = &login.expiration_time {
+        config.insert(
+            "expiration_time".to_string(),
+            expiration_time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true).into(),
+        );
+    }
+    config.insert(
+        "scopes".to_string(),
+        toml::Value::Array(login.scopes.iter().map(|s| s.clone().into()).collect()),
+    );
+
+    fs::write(&path, config.to_string())
+        .map_err(|e| format!("Failed to write Wrangler config: {}", e))
+}
+
+async fn refresh_wrangler_login(login: &WranglerLogin) -> Result<WranglerLogin, String> {
+    let refresh_token = login.refresh_token
+        .as_deref()
+        .ok_or_else(|| "Wrangler login has expired. Run `wrangler login` again.".to_string())?;
+
+    let response = Client::new()
+        .post(WRANGLER_OAUTH_TOKEN_URL)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", WRANGLER_OAUTH_CLIENT_ID),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to refresh Wrangler login: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to refresh Wrangler login (status: {}). Run `wrangler login` again.",
+            response.status()
+        ));
+    }
+
+    let token: OAuthTokenResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse OAuth response: {}", e))?;
+
+    let refreshed = WranglerLogin {
+        oauth_token: token.access_token,
+        refresh_token: token.refresh_token.or_else(|| login.refresh_token.clone()),
+        expiration_time: Some(chrono::Utc::now() + chrono::Duration::seconds(token.expires_in)),
+        scopes: token.scope
+            .map(|s| s.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_else(|| login.scopes.clone()),
+    };
+
+    save_wrangler_login(&refreshed)?;
+
+    Ok(refreshed)
+}
+
+pub async fn current_wrangler_login() -> Result<WranglerLogin, String> {
+    let login = read_wrangler_login()
+        .ok_or_else(|| "No Wrangler login found. Run `wrangler login` first.".to_string())?;
+
+    let expired = login.expiration_time
+        .map(|t| t <= chrono::Utc::now() + chrono::Duration::seconds(60))
+        .unwrap_or(false);
+
+    if expired {
+        refresh_wrangler_login(&login).await
+    } else {
+        Ok(login)
+    }
+}
+
+pub fn wrangler_permissions(login: &WranglerLogin) -> TokenPermissions {
+    let can_write = login.scopes.iter().any(|s| s == "workers_kv:write");
+
+    TokenPermissions {
+        read: Some(can_write),
+        write: Some(can_write),
+        manage_namespaces: Some(can_write),
+    }
+}
+
+pub fn environment_credentials() -> Option<EnvironmentCredentials> {
+    let api_token = std::env::var("CLOUDFLARE_API_TOKEN").ok().filter(|t| !t.is_empty())?;
+    let account_id = std::env::var("CLOUDFLARE_ACCOUNT_ID").ok().filter(|a| !a.is_empty());
+
+    Some(EnvironmentCredentials {
+        api_token,
+        account_id,
+    })
+}
@@ -0,0 +1,129 @@
+use std::future::Future;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri::{command, State};
+
+use crate::app_state::AppState;
+use crate::models::cache::{CacheKey, CacheStatus};
+use crate::models::error::ApiError;
+
+pub fn is_offline(state: &AppState) -> bool {
+    *state.offline.lock().unwrap()
+}
+
+pub fn set_offline(state: &AppState, offline: bool) {
+    *state.offline.lock().unwrap() = offline;
+}
+
+pub fn require_online(state: &AppState) -> Result<(), ApiError> {
+    if is_offline(state) {
+        return Err(ApiError::NetworkUnavailable {
+            message: "Remote namespaces are read-only while offline. Refresh once the connection is back.".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn read_cached<T: DeserializeOwned>(state: &AppState, key: &CacheKey) -> Option<(T, i64, bool)> {
+    let entry = {
+        let db = state.db.lock().unwrap();
+        match db.get_cache_entry(key) {
+            Ok(entry) => entry?,
+            Err(e) => {
+                eprintln!("Failed to read cache entry: {}", e);
+                return None;
+            }
+        }
+    };
+
+    let value = serde_json::from_str(&entry.payload).ok()?;
+    Some((value, entry.synced_at, entry.is_fresh()))
+}
+
+pub fn store_cached<T: Serialize>(state: &AppState, key: &CacheKey, value: &T) -> i64 {
+    let payload = match serde_json::to_string(value) {
+        Ok(payload) => payload,
+        Err(e) => {
+            eprintln!("Failed to serialize cache entry: {}", e);
+            return chrono::Utc::now().timestamp();
+        }
+    };
+
+    let db = state.db.lock().unwrap();
+    match db.put_cache_entry(key, &payload) {
+        Ok(synced_at) => synced_at,
+        Err(e) => {
+            eprintln!("Failed to write cache entry: {}", e);
+            chrono::Utc::now().timestamp()
+        }
+    }
+}
+
+pub fn forget_cached(state: &AppState, key: &CacheKey) {
+    let db = state.db.lock().unwrap();
+    if let Err(e) = db.remove_cache_entry(key) {
+        eprintln!("Failed to remove cache entry: {}", e);
+    }
+}
+
+pub fn invalidate_cached_keys(state: &AppState, account_id: &str, namespace_id: &str) {
+    let db = state.db.lock().unwrap();
+    if let Err(e) = db.invalidate_cached_keys(account_id, namespace_id) {
+        eprintln!("Failed to invalidate cached keys: {}", e);
+    }
+}
+
+/// Serves `key` from the cache while it is fresh, otherwise runs `fetch` and
+/// caches the result. When the network is unreachable the last cached copy is
+/// returned regardless of its age. The second element is the last sync time.
+pub async fn cached_fetch<T, F>(state: &AppState, key: CacheKey, refresh: bool, fetch: F) -> Result<(T, i64), ApiError>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T, ApiError>>,
+{
+    if !refresh {
+        if let Some((value, synced_at, true)) = read_cached(state, &key) {
+            return Ok((value, synced_at));
+        }
+    }
+
+    match fetch.await {
+        Ok(value) => {
+            set_offline(state, false);
+            let synced_at = store_cached(state, &key, &value);
+            Ok((value, synced_at))
+        }
+        Err(e) if e.is_network() => {
+            set_offline(state, true);
+            read_cached(state, &key)
+                .map(|(value, synced_at, _)| (value, synced_at))
+                .ok_or(e)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[command]
+pub fn get_remote_cache_status(state: State<AppState>) -> Result<CacheStatus, ApiError> {
+    let (entries, last_synced) = {
+        let db = state.db.lock().unwrap();
+        db.cache_summary().map_err(|e| format!("Failed to read cache: {}", e))?
+    };
+
+    Ok(CacheStatus {
+        offline: is_offline(&state),
+        entries,
+        last_synced,
+    })
+}
+
+#[command]
+pub fn clear_remote_cache(account_id: Option<String>, state: State<AppState>) -> Result<(), ApiError> {
+    let db = state.db.lock().unwrap();
+    if let Err(e) = db.clear_cache(account_id.as_deref()) {
+        return Err(format!("Failed to clear cache: {}", e).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, HashMap};
+use serde_json::Value;
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::transfer::Endpoint;
+use crate::jobs::JobHandle;
+use crate::json_diff::diff_json;
+use crate::models::diff::{DiffBatchEvent, DiffStatus, DiffSummary, KeyDiff};
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::kv::KVPermission;
+use crate::models::transfer::{KVRecord, KeyInfo, NamespaceRef};
+use crate::models::value::ValueKind;
+
+pub const DIFF_BATCH_EVENT: &str = "diff-batch";
+const DIFF_BATCH_SIZE: usize = 100;
+
+fn one_sided(info: &KeyInfo, status: DiffStatus) -> KeyDiff {
+    let (left, right) = match status {
+        DiffStatus::OnlyRight => (None, Some(info)),
+        _ => (Some(info), None),
+    };
+
+    KeyDiff {
+        key: info.key.clone(),
+        status,
+        value_changed: true,
+        metadata_changed: info.metadata.is_some(),
+        expiration_changed: info.expiration.is_some(),
+        left_expiration: left.and_then(|i| i.expiration),
+        right_expiration: right.and_then(|i| i.expiration),
+        left_metadata: left.and_then(|i| i.metadata.clone()),
+        right_metadata: right.and_then(|i| i.metadata.clone()),
+        json_changes: vec![],
+        json_changes_truncated: false,
+    }
+}
+
+pub fn compare_records(left: &KVRecord, right: &KVRecord) -> Option<KeyDiff> {
+    let value_changed = left.value.bytes != right.value.bytes;
+    let metadata_changed = left.metadata != right.metadata;
+    let expiration_changed = left.expiration != right.expiration;
+
+    if !value_changed && !metadata_changed && !expiration_changed {
+        return None;
+    }
+
+    let (json_changes, json_changes_truncated) = match (value_changed, left.value.kind, right.value.kind) {
+        (true, ValueKind::Json, ValueKind::Json) => {
+            match (
+                serde_json::from_slice::<Value>(&left.value.bytes),
+                serde_json::from_slice::<Value>(&right.value.bytes),
+            ) {
+                (Ok(l), Ok(r)) => diff_json(&l, &r),
+                _ => (vec![], false),
+            }
+        }
+        _ => (vec![], false),
+    };
+
+    Some(KeyDiff {
+        key: left.key.clone(),
+        status: DiffStatus::Changed,
+        value_changed,
+        metadata_changed,
+        expiration_changed,
+        left_expiration: left.expiration,
+        right_expiration: right.expiration,
+        left_metadata: left.metadata.clone(),
+        right_metadata: right.metadata.clone(),
+        json_changes,
+        json_changes_truncated,
+    })
+}
+
+fn flush(job: &JobHandle, diffs: &mut Vec<KeyDiff>) {
+    if diffs.is_empty() {
+        return;
+    }
+
+    job.emit(DIFF_BATCH_EVENT, DiffBatchEvent {
+        job_id: job.id(),
+        diffs: std::mem::take(diffs),
+    });
+}
+
+async fn run_diff(
+    state: &AppState,
+    job: &JobHandle,
+    left: &mut Endpoint,
+    right: &mut Endpoint
+) -> Result<DiffSummary, ApiError> {
+    let left_keys: BTreeMap<String, KeyInfo> = left.list(state, job).await?.into_iter().map(|k| (k.key.clone(), k)).collect();
+    let right_keys: BTreeMap<String, KeyInfo> = right.list(state, job).await?.into_iter().map(|k| (k.key.clone(), k)).collect();
+
+    let mut summary = DiffSummary::default();
+    let mut diffs = Vec::new();
+    let mut shared = Vec::new();
+
+    for (key, info) in &left_keys {
+        match right_keys.get(key) {
+            Some(right_info) => shared.push((info.clone(), right_info.clone())),
+            None => {
+                summary.only_left += 1;
+                diffs.push(one_sided(info, DiffStatus::OnlyLeft));
+            }
+        }
+    }
+    for (key, info) in &right_keys {
+        if !left_keys.contains_key(key) {
+            summary.only_right += 1;
+            diffs.push(one_sided(info, DiffStatus::OnlyRight));
+        }
+    }
+    flush(job, &mut diffs);
+
+    let total = shared.len() as u64;
+    let mut compared = 0;
+
+    for chunk in shared.chunks(DIFF_BATCH_SIZE) {
+        job.ensure_active::<ApiError>()?;
+
+        let (left_infos, right_infos): (Vec<KeyInfo>, Vec<KeyInfo>) = chunk.iter().cloned().unzip();
+        let left_records: HashMap<String, KVRecord> = left.fetch(job, left_infos).await?
+            .into_iter()
+            .map(|r| (r.key.clone(), r))
+            .collect();
+        let right_records: HashMap<String, KVRecord> = right.fetch(job, right_infos).await?
+            .into_iter()
+            .map(|r| (r.key.clone(), r))
+            .collect();
+
+        for (left_info, right_info) in chunk {
+            match (left_records.get(&left_info.key), right_records.get(&right_info.key)) {
+                (Some(l), Some(r)) => match compare_records(l, r) {
+                    Some(diff) => {
+                        summary.changed += 1;
+                        diffs.push(diff);
+                    }
+                    None => summary.identical += 1,
+                },
+                (Some(_), None) => {
+                    summary.only_left += 1;
+                    diffs.push(one_sided(left_info, DiffStatus::OnlyLeft));
+                }
+                (None, Some(_)) => {
+                    summary.only_right += 1;
+                    diffs.push(one_sided(right_info, DiffStatus::OnlyRight));
+                }
+                (None, None) => {}
+            }
+        }
+        flush(job, &mut diffs);
+
+        compared += chunk.len() as u64;
+        job.progress(compared, Some(total), Some("Comparing values".to_string()));
+    }
+
+    Ok(summary)
+}
+
+/// Compares two namespaces on any backend. Differences are emitted in
+/// `diff-batch` events while the job runs; the job result is the summary.
+#[command]
+pub async fn diff_namespaces(
+    app: AppHandle,
+    left: NamespaceRef,
+    right: NamespaceRef,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let mut left = Endpoint::resolve(&state, &left, KVPermission::Read).await?;
+    let mut right = Endpoint::resolve(&state, &right, KVPermission::Read).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "diff_namespaces", move |job| async move {
+        let state = task_app.state::<AppState>();
+        run_diff(&state, &job, &mut left, &mut right).await
+    });
+
+    Ok(job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::models::diff::JsonChangeKind;
+    use crate::models::value::KVValue;
+
+    fn record(key: &str, value: &str, metadata: Option<Value>) -> KVRecord {
+        KVRecord {
+            key: key.to_string(),
+            value: KVValue::from_bytes(value.as_bytes().to_vec()),
+            expiration: None,
+            metadata,
+            modified_at: None,
+        }
+    }
+
+    #[test]
+    fn identical_records_have_no_diff() {
+        let left = record("a", r#"{"v":1}"#, Some(json!({"m": 1})));
+        assert!(compare_records(&left, &left.clone()).is_none());
+    }
+
+    #[test]
+    fn json_values_get_field_changes() {
+        let diff = compare_records(&record("a", r#"{"v":1,"w":2}"#, None), &record("a", r#"{"v":2,"w":2}"#, None)).unwrap();
+
+        assert_eq!(diff.status, DiffStatus::Changed);
+        assert!(diff.value_changed && !diff.metadata_changed && !diff.expiration_changed);
+        assert_eq!(diff.json_changes.len(), 1);
+        assert_eq!((diff.json_changes[0].path.as_str(), diff.json_changes[0].kind), ("v", JsonChangeKind::Changed));
+    }
+
+    #[test]
+    fn text_and_metadata_changes_have_no_field_changes() {
+        let diff = compare_records(&record("a", "hello", None), &record("a", "world", None)).unwrap();
+        assert!(diff.value_changed);
+        assert!(diff.json_changes.is_empty());
+
+        let mut right = record("a", "hello", Some(json!({"m": 1})));
+        right.expiration = Some(100);
+        let diff = compare_records(&record("a", "hello", None), &right).unwrap();
+        assert!(!diff.value_changed && diff.metadata_changed && diff.expiration_changed);
+        assert_eq!((diff.left_expiration, diff.right_expiration), (None, Some(100)));
+        assert!(diff.json_changes.is_empty());
+    }
+
+    #[test]
+    fn one_sided_keys_keep_their_side() {
+        let info = KeyInfo { key: "a".to_string(), expiration: Some(5), metadata: Some(json!({"m": 1})) };
+
+        let diff = one_sided(&info, DiffStatus::OnlyRight);
+        assert_eq!((diff.left_expiration, diff.right_expiration), (None, Some(5)));
+        assert_eq!((diff.left_metadata, diff.right_metadata), (None, Some(json!({"m": 1}))));
+
+        let diff = one_sided(&info, DiffStatus::OnlyLeft);
+        assert_eq!((diff.left_expiration, diff.right_expiration), (Some(5), None));
+        assert!(diff.value_changed && diff.metadata_changed && diff.expiration_changed);
+    }
+}
@@ -0,0 +1,572 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::local::read_local_page;
+use crate::handlers::remote::{fetch_remote_key_page, fetch_remote_records};
+use crate::handlers::transfer::Endpoint;
+use crate::jobs::JobHandle;
+use crate::models::cloudflare::CloudflareBulkWrite;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::kv::KVPermission;
+use crate::models::transfer::{
+    DirectoryManifest, ExportFilter, ExportSummary, KVRecord, KeyInfo, ManifestEntry, NamespaceRef, NdjsonCheckpoint,
+    NdjsonExportOptions, NdjsonExportSummary, DIRECTORY_MANIFEST_VERSION
+};
+use crate::models::value::ValueKind;
+
+const EXPORT_BATCH_SIZE: usize = 500;
+const LOCAL_PAGE_SIZE: usize = 1000;
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const KEYS_DIR: &str = "keys";
+const VALUE_EXTENSIONS: [&str; 3] = ["json", "txt", "bin"];
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.');
+const ESCAPED_DIR: &str = "~";
+const ESCAPED_CHUNK: usize = 64;
+
+fn io_error(e: std::io::Error) -> ApiError {
+    format!("Failed to write export file: {}", e).into()
+}
+
+pub fn filter_keys(keys: Vec<KeyInfo>, filter: &ExportFilter) -> Vec<KeyInfo> {
+    let wanted: Option<HashSet<&str>> = filter.keys.as_ref().map(|keys| keys.iter().map(String::as_str).collect());
+
+    keys.into_iter()
+        .filter(|k| filter.prefix.as_ref().is_none_or(|prefix| k.key.starts_with(prefix.as_str())))
+        .filter(|k| wanted.as_ref().is_none_or(|wanted| wanted.contains(k.key.as_str())))
+        .collect()
+}
+
+fn value_extension(record: &KVRecord) -> &'static str {
+    match record.value.kind {
+        ValueKind::Json => "json",
+        ValueKind::Text => "txt",
+        ValueKind::Binary | ValueKind::Opaque => "bin",
+    }
+}
+
+/// `~` is always percent-encoded here, which leaves the `~` folder to `escaped_key_path`.
+pub fn key_to_path(key: &str, extension: &str) -> String {
+    let segments: Vec<&str> = key.split('/').collect();
+    let encoded = if segments.iter().any(|s| s.is_empty() || *s == "." || *s == "..") {
+        utf8_percent_encode(key, SEGMENT).to_string()
+    } else {
+        segments.iter().map(|s| utf8_percent_encode(s, SEGMENT).to_string()).collect::<Vec<_>>().join("/")
+    };
+
+    format!("{}.{}", encoded, extension)
+}
+
+/// Hex-encodes the whole key, so the path is unique regardless of case or shape.
+pub fn escaped_key_path(key: &str, extension: &str) -> String {
+    let chunks: Vec<String> = key
+        .as_bytes()
+        .chunks(ESCAPED_CHUNK)
+        .map(|chunk| chunk.iter().map(|b| format!("{:02x}", b)).collect())
+        .collect();
+
+    format!("{}/{}.{}", ESCAPED_DIR, chunks.join("/"), extension)
+}
+
+/// Hands out export paths, switching to the escaped form when a key's natural path
+/// would land on a file or folder already taken, ignoring case.
+#[derive(Default)]
+pub struct ExportPaths {
+    files: HashSet<String>,
+    dirs: HashSet<String>,
+}
+
+impl ExportPaths {
+    pub fn assign(&mut self, key: &str, extension: &str) -> String {
+        let natural = key_to_path(key, extension);
+        let folded = natural.to_lowercase();
+        let parents: Vec<&str> = folded.match_indices('/').map(|(index, _)| &folded[..index]).collect();
+
+        let clashes = self.files.contains(&folded)
+            || self.dirs.contains(&folded)
+            || parents.iter().any(|parent| self.files.contains(*parent));
+        let path = if clashes { escaped_key_path(key, extension) } else { natural };
+
+        let folded = path.to_lowercase();
+        self.dirs.extend(folded.match_indices('/').map(|(index, _)| folded[..index].to_string()));
+        self.files.insert(folded);
+        path
+    }
+}
+
+fn decode_escaped(path: &str, hex: &str) -> Result<String, String> {
+    let digits: Vec<u8> = hex.bytes().filter(|b| *b != b'/').collect();
+    let bytes = digits
+        .chunks_exact(2)
+        .map(|pair| std::str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .filter(|bytes| bytes.len() * 2 == digits.len())
+        .ok_or_else(|| format!("{} is not a valid escaped key", path))?;
+
+    String::from_utf8(bytes).map_err(|_| format!("{} does not decode to a UTF-8 key", path))
+}
+
+pub fn path_to_key(path: &str) -> Result<String, String> {
+    let stem = path
+        .rsplit_once('.')
+        .filter(|(_, extension)| VALUE_EXTENSIONS.contains(extension))
+        .map(|(stem, _)| stem)
+        .ok_or_else(|| format!("{} does not end in .json, .txt or .bin", path))?;
+
+    if let Some(hex) = stem.strip_prefix(ESCAPED_DIR).and_then(|rest| rest.strip_prefix('/')) {
+        return decode_escaped(path, hex);
+    }
+
+    stem.split('/')
+        .map(|segment| percent_decode_str(segment).decode_utf8().map(|s| s.into_owned()))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|segments| segments.join("/"))
+        .map_err(|_| format!("{} does not decode to a UTF-8 key", path))
+}
+
+fn prepare_directory(root: &Path) -> Result<PathBuf, ApiError> {
+    let keys_dir = root.join(KEYS_DIR);
+    if keys_dir.exists() {
+        if !root.join(MANIFEST_FILE).exists() {
+            return Err(format!("{} already has a {} folder that is not from an export", root.display(), KEYS_DIR).into());
+        }
+        fs::remove_dir_all(&keys_dir).map_err(io_error)?;
+    }
+
+    fs::create_dir_all(&keys_dir).map_err(io_error)?;
+    Ok(keys_dir)
+}
+
+async fn write_directory(
+    state: &AppState,
+    job: &JobHandle,
+    mut endpoint: Endpoint,
+    path: String,
+    filter: ExportFilter
+) -> Result<ExportSummary, ApiError> {
+    let keys = filter_keys(endpoint.list(state, job).await?, &filter);
+    let total = keys.len() as u64;
+
+    let root = PathBuf::from(&path);
+    let keys_dir = prepare_directory(&root)?;
+    let mut manifest = DirectoryManifest { version: DIRECTORY_MANIFEST_VERSION, keys: BTreeMap::new() };
+    let mut paths = ExportPaths::default();
+    let mut expired = 0;
+    let mut processed = 0;
+
+    for chunk in keys.chunks(EXPORT_BATCH_SIZE) {
+        job.ensure_active::<ApiError>()?;
+
+        let now = chrono::Utc::now().timestamp();
+        for record in endpoint.fetch(job, chunk.to_vec()).await? {
+            if record.is_expired(now) {
+                expired += 1;
+                continue;
+            }
+
+            let relative = paths.assign(&record.key, value_extension(&record));
+
+            let file_path = keys_dir.join(&relative);
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).map_err(io_error)?;
+            }
+            fs::write(&file_path, &record.value.bytes).map_err(io_error)?;
+
+            manifest.keys.insert(relative, ManifestEntry {
+                key: record.key,
+                expiration: record.expiration,
+                metadata: record.metadata,
+            });
+        }
+
+        processed += chunk.len() as u64;
+        job.progress(processed, Some(total), Some("Exporting keys".to_string()));
+    }
+
+    let mut contents = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    contents.push(b'\n');
+    fs::write(root.join(MANIFEST_FILE), contents).map_err(io_error)?;
+
+    Ok(ExportSummary { path, exported: manifest.keys.len(), expired })
+}
+
+/// Writes the namespace as a JSON array in the `wrangler kv bulk put` format.
+/// Values are fetched and written a batch at a time into a `.partial` file
+/// that replaces the target once everything has been written.
+async fn write_bulk_json(
+    state: &AppState,
+    job: &JobHandle,
+    mut endpoint: Endpoint,
+    path: String,
+    filter: ExportFilter
+) -> Result<ExportSummary, ApiError> {
+    let keys = filter_keys(endpoint.list(state, job).await?, &filter);
+    let total = keys.len() as u64;
+
+    let partial_path = format!("{}.partial", path);
+    let mut writer = BufWriter::new(File::create(&partial_path).map_err(io_error)?);
+    let mut exported = 0;
+    let mut expired = 0;
+    let mut processed = 0;
+
+    writer.write_all(b"[").map_err(io_error)?;
+
+    let outcome: Result<(), ApiError> = async {
+        for chunk in keys.chunks(EXPORT_BATCH_SIZE) {
+            job.ensure_active::<ApiError>()?;
+
+            let now = chrono::Utc::now().timestamp();
+            for record in endpoint.fetch(job, chunk.to_vec()).await? {
+                if record.is_expired(now) {
+                    expired += 1;
+                    continue;
+                }
+
+                writer.write_all(if exported == 0 { b"\n  " } else { b",\n  " }).map_err(io_error)?;
+                serde_json::to_writer(&mut writer, &CloudflareBulkWrite::from(&record))
+                    .map_err(|e| format!("Failed to serialize {}: {}", record.key, e))?;
+                exported += 1;
+            }
+
+            processed += chunk.len() as u64;
+            job.progress(processed, Some(total), Some("Exporting keys".to_string()));
+        }
+
+        writer.write_all(b"\n]\n").map_err(io_error)?;
+        writer.flush().map_err(io_error)
+    }.await;
+
+    if let Err(e) = outcome.and_then(|_| fs::rename(&partial_path, &path).map_err(io_error)) {
+        fs::remove_file(&partial_path).ok();
+        return Err(e);
+    }
+
+    Ok(ExportSummary { path, exported, expired })
+}
+
+#[command]
+pub async fn export_bulk_json(
+    app: AppHandle,
+    namespace: NamespaceRef,
+    path: String,
+    filter: Option<ExportFilter>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let endpoint = Endpoint::resolve(&state, &namespace, KVPermission::Read).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "export_bulk_json", move |job| async move {
+        let state = task_app.state::<AppState>();
+        write_bulk_json(&state, &job, endpoint, path, filter.unwrap_or_default()).await
+    });
+
+    Ok(job_id)
+}
+
+#[command]
+pub async fn export_directory(
+    app: AppHandle,
+    namespace: NamespaceRef,
+    path: String,
+    filter: Option<ExportFilter>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let endpoint = Endpoint::resolve(&state, &namespace, KVPermission::Read).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "export_directory", move |job| async move {
+        let state = task_app.state::<AppState>();
+        write_directory(&state, &job, endpoint, path, filter.unwrap_or_default()).await
+    });
+
+    Ok(job_id)
+}
+
+async fn read_page(
+    state: &AppState,
+    job: &JobHandle,
+    endpoint: &Endpoint,
+    cursor: Option<String>,
+    filter: &ExportFilter
+) -> Result<(Vec<KVRecord>, Option<String>), ApiError> {
+    match endpoint {
+        Endpoint::Local { kv_path, namespace_name, .. } => {
+            let (kv_path, namespace_name, prefix) = (kv_path.clone(), namespace_name.clone(), filter.prefix.clone());
+            let (records, next) = tauri::async_runtime::spawn_blocking(move || {
+                read_local_page(&kv_path, &namespace_name, cursor.as_deref(), prefix.as_deref(), LOCAL_PAGE_SIZE)
+            })
+            .await
+            .map_err(|e| format!("Failed to read local namespace: {}", e))??;
+
+            let wanted = filter_keys(records.iter().map(KeyInfo::from).collect(), filter);
+            let wanted: HashSet<&str> = wanted.iter().map(|k| k.key.as_str()).collect();
+            let records = records.iter().filter(|r| wanted.contains(r.key.as_str())).cloned().collect();
+            Ok((records, next))
+        }
+        Endpoint::Remote { connection, namespace_id } => {
+            let client = reqwest::Client::new();
+            let page = fetch_remote_key_page(state, &client, connection, namespace_id, cursor.as_deref(), filter.prefix.as_deref()).await?;
+            let keys = filter_keys(page.entries.into_iter().map(KeyInfo::from).collect(), filter);
+            let records = fetch_remote_records(job, connection, namespace_id, keys).await?;
+            Ok((records, page.cursor.filter(|c| !c.is_empty())))
+        }
+    }
+}
+
+fn encode_lines(lines: &[u8], gzip: bool) -> Result<Vec<u8>, ApiError> {
+    if !gzip {
+        return Ok(lines.to_vec());
+    }
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(lines).map_err(io_error)?;
+    encoder.finish().map_err(io_error)
+}
+
+fn save_checkpoint(path: &str, checkpoint: &NdjsonCheckpoint) -> Result<(), ApiError> {
+    let contents = serde_json::to_vec(checkpoint).map_err(|e| format!("Failed to serialize checkpoint: {}", e))?;
+    fs::write(path, contents).map_err(io_error)
+}
+
+fn open_partial(
+    partial_path: &str,
+    checkpoint_path: &str,
+    namespace: &NamespaceRef,
+    options: &NdjsonExportOptions,
+    resume: bool
+) -> Result<(File, Option<NdjsonCheckpoint>), ApiError> {
+    let checkpoint = fs::read(checkpoint_path)
+        .ok()
+        .and_then(|contents| serde_json::from_slice::<NdjsonCheckpoint>(&contents).ok())
+        .filter(|c| resume && c.namespace.descriptor() == namespace.descriptor() && &c.options == options);
+
+    if let Some(checkpoint) = checkpoint {
+        if let Ok(mut file) = OpenOptions::new().write(true).open(partial_path) {
+            file.set_len(checkpoint.offset).map_err(io_error)?;
+            file.seek(SeekFrom::End(0)).map_err(io_error)?;
+            return Ok((file, Some(checkpoint)));
+        }
+    }
+
+    fs::remove_file(checkpoint_path).ok();
+    Ok((File::create(partial_path).map_err(io_error)?, None))
+}
+
+/// With gzip each page is its own member, so the file can be cut back to any checkpoint.
+async fn write_ndjson(
+    state: &AppState,
+    job: &JobHandle,
+    endpoint: Endpoint,
+    (namespace, path): (NamespaceRef, String),
+    options: NdjsonExportOptions,
+    resume: bool
+) -> Result<NdjsonExportSummary, ApiError> {
+    let partial_path = format!("{}.partial", path);
+    let checkpoint_path = format!("{}.checkpoint", path);
+    let (mut file, previous) = open_partial(&partial_path, &checkpoint_path, &namespace, &options, resume)?;
+
+    let resumed = previous.is_some();
+    let mut checkpoint = previous.unwrap_or_else(|| NdjsonCheckpoint {
+        namespace: namespace.clone(),
+        options: options.clone(),
+        cursor: None,
+        offset: 0,
+        exported: 0,
+        expired: 0,
+        updated_at: 0,
+    });
+
+    loop {
+        job.ensure_active::<ApiError>()?;
+
+        let (records, next) = read_page(state, job, &endpoint, checkpoint.cursor.clone(), &options.filter).await?;
+        let now = chrono::Utc::now().timestamp();
+        let mut lines = Vec::new();
+
+        for record in records {
+            if record.is_expired(now) {
+                checkpoint.expired += 1;
+                continue;
+            }
+
+            serde_json::to_writer(&mut lines, &CloudflareBulkWrite::from(&record))
+                .map_err(|e| format!("Failed to serialize {}: {}", record.key, e))?;
+            lines.push(b'\n');
+            checkpoint.exported += 1;
+        }
+
+        let encoded = encode_lines(&lines, options.gzip)?;
+        file.write_all(&encoded).map_err(io_error)?;
+        file.sync_data().map_err(io_error)?;
+
+        checkpoint.offset += encoded.len() as u64;
+        checkpoint.cursor = next;
+        checkpoint.updated_at = now;
+        save_checkpoint(&checkpoint_path, &checkpoint)?;
+
+        job.progress(
+            (checkpoint.exported + checkpoint.expired) as u64,
+            None,
+            Some("Exporting keys".to_string())
+        );
+
+        if checkpoint.cursor.is_none() {
+            break;
+        }
+    }
+
+    let summary = NdjsonExportSummary {
+        path: path.clone(),
+        namespace,
+        exported: checkpoint.exported,
+        expired: checkpoint.expired,
+        gzip: options.gzip,
+        resumed,
+        completed_at: chrono::Utc::now().timestamp(),
+    };
+    let mut line = serde_json::to_vec(&serde_json::json!({ "summary": summary }))
+        .map_err(|e| format!("Failed to serialize export summary: {}", e))?;
+    line.push(b'\n');
+
+    file.write_all(&encode_lines(&line, options.gzip)?).map_err(io_error)?;
+    file.sync_data().map_err(io_error)?;
+    drop(file);
+
+    fs::rename(&partial_path, &path).map_err(io_error)?;
+    fs::remove_file(&checkpoint_path).ok();
+
+    Ok(summary)
+}
+
+#[command]
+pub async fn export_ndjson(
+    app: AppHandle,
+    namespace: NamespaceRef,
+    path: String,
+    options: Option<NdjsonExportOptions>,
+    resume: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let endpoint = Endpoint::resolve(&state, &namespace, KVPermission::Read).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "export_ndjson", move |job| async move {
+        let state = task_app.state::<AppState>();
+        write_ndjson(&state, &job, endpoint, (namespace, path), options.unwrap_or_default(), resume.unwrap_or(true)).await
+    });
+
+    Ok(job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use flate2::read::MultiGzDecoder;
+
+    fn scratch(name: &str) -> String {
+        std::env::temp_dir().join(format!("kv-export-{}-{}", std::process::id(), name)).to_string_lossy().to_string()
+    }
+
+    fn namespace() -> NamespaceRef {
+        NamespaceRef::Local { folder_id: 1, namespace_id: "folder-1-ns-cache".to_string() }
+    }
+
+    fn checkpoint(options: &NdjsonExportOptions, offset: u64) -> NdjsonCheckpoint {
+        NdjsonCheckpoint {
+            namespace: namespace(),
+            options: options.clone(),
+            cursor: Some("b".to_string()),
+            offset,
+            exported: 1,
+            expired: 0,
+            updated_at: 0,
+        }
+    }
+
+    fn gunzip(path: &str) -> String {
+        let mut text = String::new();
+        MultiGzDecoder::new(File::open(path).unwrap()).read_to_string(&mut text).unwrap();
+        text
+    }
+
+    #[test]
+    fn resuming_cuts_a_gzip_export_back_to_the_last_page() {
+        let (partial, saved) = (scratch("resume.partial"), scratch("resume.checkpoint"));
+        let options = NdjsonExportOptions { gzip: true, ..NdjsonExportOptions::default() };
+
+        let first = encode_lines(b"{\"key\":\"a\"}\n", true).unwrap();
+        let torn = encode_lines(b"{\"key\":\"b\"}\n", true).unwrap();
+        fs::write(&partial, [&first[..], &torn[..torn.len() / 2]].concat()).unwrap();
+        save_checkpoint(&saved, &checkpoint(&options, first.len() as u64)).unwrap();
+
+        let (mut file, previous) = open_partial(&partial, &saved, &namespace(), &options, true).unwrap();
+        assert_eq!(previous.unwrap().cursor.as_deref(), Some("b"));
+        file.write_all(&encode_lines(b"{\"key\":\"b\"}\n", true).unwrap()).unwrap();
+        drop(file);
+
+        assert_eq!(gunzip(&partial), "{\"key\":\"a\"}\n{\"key\":\"b\"}\n");
+        fs::remove_file(&partial).unwrap();
+        fs::remove_file(&saved).unwrap();
+    }
+
+    #[test]
+    fn a_checkpoint_for_other_options_starts_over() {
+        let (partial, saved) = (scratch("restart.partial"), scratch("restart.checkpoint"));
+        let options = NdjsonExportOptions { gzip: true, ..NdjsonExportOptions::default() };
+
+        fs::write(&partial, encode_lines(b"{\"key\":\"a\"}\n", true).unwrap()).unwrap();
+        save_checkpoint(&saved, &checkpoint(&options, 10)).unwrap();
+
+        let plain = NdjsonExportOptions::default();
+        let (_, previous) = open_partial(&partial, &saved, &namespace(), &plain, true).unwrap();
+        assert!(previous.is_none());
+        assert_eq!(fs::metadata(&partial).unwrap().len(), 0);
+        assert!(!Path::new(&saved).exists());
+        fs::remove_file(&partial).unwrap();
+    }
+
+    #[test]
+    fn key_paths_round_trip() {
+        for key in ["plain", "users/42/profile", "a b/ü?", "~home", "a//b", "../up"] {
+            let path = key_to_path(key, "json");
+            assert!(!path.contains('~'));
+            assert_eq!(path_to_key(&path).unwrap(), key);
+            assert_eq!(path_to_key(&escaped_key_path(key, "txt")).unwrap(), key);
+        }
+
+        assert_eq!(key_to_path("users/42", "json"), "users/42.json");
+        assert!(path_to_key("~/6.json").is_err());
+        assert!(path_to_key("notes.md").is_err());
+    }
+
+    #[test]
+    fn keys_that_clash_with_a_folder_are_escaped() {
+        let mut paths = ExportPaths::default();
+        assert_eq!(paths.assign("a.json/b", "json"), "a.json/b.json");
+        assert_eq!(paths.assign("a", "json"), escaped_key_path("a", "json"));
+
+        let mut paths = ExportPaths::default();
+        assert_eq!(paths.assign("a", "json"), "a.json");
+        assert_eq!(paths.assign("a.json/b", "json"), escaped_key_path("a.json/b", "json"));
+    }
+
+    #[test]
+    fn keys_that_differ_only_in_case_are_escaped() {
+        let mut paths = ExportPaths::default();
+        assert_eq!(paths.assign("Config", "json"), "Config.json");
+        let escaped = paths.assign("config", "json");
+        assert_eq!(escaped, escaped_key_path("config", "json"));
+        assert_eq!(path_to_key(&escaped).unwrap(), "config");
+
+        assert_eq!(paths.assign("USERS/1", "txt"), "USERS/1.txt");
+        assert_eq!(paths.assign("users/2", "txt"), "users/2.txt");
+    }
+}
@@ -0,0 +1,400 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use flate2::read::MultiGzDecoder;
+use serde_json::Value;
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::export::{path_to_key, KEYS_DIR, MANIFEST_FILE};
+use crate::handlers::transfer::{apply_records, replace_records, Endpoint};
+use crate::jobs::JobHandle;
+use crate::models::cloudflare::CloudflareBulkWrite;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::kv::KVPermission;
+use crate::models::transfer::{
+    CsvColumns, DirectoryImportOptions, DirectoryManifest, ImportFormat, ImportIssue, ImportOptions, ImportSummary, KVRecord,
+    NamespaceRef, TransferOptions
+};
+use crate::models::value::KVValue;
+
+const PROGRESS_INTERVAL: usize = 1000;
+
+type ParsedEntry = (usize, Result<CloudflareBulkWrite, String>);
+
+fn open_file(path: &str) -> Result<File, String> {
+    File::open(path).map_err(|e| format!("Failed to open {}: {}", path, e))
+}
+
+fn parse_bulk_json(path: &str) -> Result<Vec<ParsedEntry>, String> {
+    let entries: Vec<Value> = serde_json::from_reader(BufReader::new(open_file(path)?))
+        .map_err(|e| format!("Not a bulk JSON array: {}", e))?;
+
+    Ok(entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, entry)| (index + 1, serde_json::from_value(entry).map_err(|e| e.to_string())))
+        .collect())
+}
+
+fn parse_ndjson_line(line: &str) -> Option<Result<CloudflareBulkWrite, String>> {
+    match serde_json::from_str::<Value>(line) {
+        // Summary record written at the end of a streaming export.
+        Ok(value) if value.get("summary").is_some() && value.get("key").is_none() => None,
+        Ok(value) => Some(serde_json::from_value(value).map_err(|e| e.to_string())),
+        Err(e) => Some(Err(e.to_string())),
+    }
+}
+
+fn parse_ndjson(path: &str, job: &JobHandle) -> Result<Vec<ParsedEntry>, String> {
+    let file = open_file(path)?;
+    let reader: Box<dyn BufRead> = if path.ends_with(".gz") {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    let mut entries = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(entry) = parse_ndjson_line(&line) else {
+            continue;
+        };
+        entries.push((index + 1, entry));
+        if entries.len() % PROGRESS_INTERVAL == 0 {
+            job.ensure_active::<String>()?;
+            job.progress(entries.len() as u64, None, Some("Reading file".to_string()));
+        }
+    }
+
+    Ok(entries)
+}
+
+fn parse_csv_row(row: &csv::StringRecord, columns: &[Option<usize>; 4]) -> Result<CloudflareBulkWrite, String> {
+    let field = |index: Option<usize>| index.and_then(|i| row.get(i)).filter(|v| !v.is_empty());
+
+    let key = field(columns[0]).ok_or_else(|| "Key column is empty".to_string())?;
+    let metadata = field(columns[2])
+        .map(|m| serde_json::from_str(m).map_err(|e| format!("Metadata is not valid JSON: {}", e)))
+        .transpose()?;
+    let expiration = field(columns[3])
+        .map(|e| e.trim().parse::<i64>().map_err(|_| format!("\"{}\" is not a Unix timestamp", e)))
+        .transpose()?;
+
+    Ok(CloudflareBulkWrite {
+        key: key.to_string(),
+        value: field(columns[1]).unwrap_or_default().to_string(),
+        expiration,
+        expiration_ttl: None,
+        metadata,
+        base64: false,
+    })
+}
+
+fn parse_csv(path: &str, columns: &CsvColumns, job: &JobHandle) -> Result<Vec<ParsedEntry>, String> {
+    let mut reader = csv::Reader::from_reader(open_file(path)?);
+    let headers = reader.headers().map_err(|e| format!("Failed to read CSV header: {}", e))?.clone();
+    let position = |name: &str| headers.iter().position(|h| h.trim() == name);
+
+    let required = |name: &str| position(name).ok_or_else(|| format!("CSV has no \"{}\" column", name));
+    let indices = [
+        Some(required(&columns.key)?),
+        Some(required(&columns.value)?),
+        columns.metadata.as_deref().map(required).transpose()?,
+        columns.expiration.as_deref().map(required).transpose()?,
+    ];
+
+    let mut entries = Vec::new();
+    for row in reader.records() {
+        let entry = match row {
+            Ok(row) => {
+                let line = row.position().map(|p| p.line() as usize).unwrap_or_default();
+                (line, parse_csv_row(&row, &indices))
+            }
+            Err(e) => (e.position().map(|p| p.line() as usize).unwrap_or_default(), Err(e.to_string())),
+        };
+        entries.push(entry);
+
+        if entries.len() % PROGRESS_INTERVAL == 0 {
+            job.ensure_active::<String>()?;
+            job.progress(entries.len() as u64, None, Some("Reading file".to_string()));
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Turns parsed entries into records, collecting the ones that do not pass
+/// validation. When a key appears twice the later entry wins.
+fn collect_records(entries: Vec<ParsedEntry>) -> (Vec<KVRecord>, Vec<ImportIssue>) {
+    let now = chrono::Utc::now().timestamp();
+    let mut records: Vec<KVRecord> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut issues = Vec::new();
+
+    for (line, entry) in entries {
+        let key = entry.as_ref().ok().map(|e| e.key.clone());
+        let record = entry
+            .and_then(|e| e.into_record(now))
+            .and_then(|record| record.validate().map(|_| record));
+
+        match record {
+            Ok(record) => match positions.get(&record.key) {
+                Some(&index) => {
+                    issues.push(ImportIssue {
+                        line,
+                        file: None,
+                        key: Some(record.key.clone()),
+                        message: "Duplicate key; this entry replaces the earlier one".to_string(),
+                    });
+                    records[index] = record;
+                }
+                None => {
+                    positions.insert(record.key.clone(), records.len());
+                    records.push(record);
+                }
+            },
+            Err(message) => issues.push(ImportIssue { line, file: None, key, message }),
+        }
+    }
+
+    (records, issues)
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let relative = if prefix.is_empty() { name } else { format!("{}/{}", prefix, name) };
+
+        if entry.path().is_dir() {
+            collect_files(&entry.path(), &relative, files)?;
+        } else {
+            files.push(relative);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_directory(root: &str, job: &JobHandle) -> Result<(Vec<KVRecord>, Vec<ImportIssue>), String> {
+    let root = Path::new(root);
+    let keys_dir = root.join(KEYS_DIR);
+    if !keys_dir.is_dir() {
+        return Err(format!("{} has no {} folder", root.display(), KEYS_DIR));
+    }
+
+    let mut manifest = match fs::read(root.join(MANIFEST_FILE)) {
+        Ok(contents) => serde_json::from_slice::<DirectoryManifest>(&contents)
+            .map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?
+            .keys,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", MANIFEST_FILE, e)),
+    };
+
+    let mut files = Vec::new();
+    collect_files(&keys_dir, "", &mut files)?;
+    files.sort();
+
+    let mut records: BTreeMap<String, KVRecord> = BTreeMap::new();
+    let mut issues = Vec::new();
+    let issue = |file: &str, key: Option<String>, message: String| ImportIssue { line: 0, file: Some(file.to_string()), key, message };
+
+    for (index, file) in files.iter().enumerate() {
+        let (key, expiration, metadata) = match manifest.remove(file) {
+            Some(entry) => (entry.key, entry.expiration, entry.metadata),
+            None => match path_to_key(file) {
+                Ok(key) => (key, None, None),
+                Err(message) => {
+                    issues.push(issue(file, None, message));
+                    continue;
+                }
+            },
+        };
+
+        let bytes = fs::read(keys_dir.join(file)).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+        let record = KVRecord { key, value: KVValue::from_bytes(bytes), expiration, metadata, modified_at: None };
+        if let Err(message) = record.validate() {
+            issues.push(issue(file, Some(record.key), message));
+            continue;
+        }
+
+        if records.contains_key(&record.key) {
+            issues.push(issue(file, Some(record.key.clone()), "Duplicate key; this file replaces the earlier one".to_string()));
+        }
+        records.insert(record.key.clone(), record);
+
+        if (index + 1) % PROGRESS_INTERVAL == 0 {
+            job.ensure_active::<String>()?;
+            job.progress((index + 1) as u64, Some(files.len() as u64), Some("Reading files".to_string()));
+        }
+    }
+
+    for (file, entry) in manifest {
+        issues.push(issue(&file, Some(entry.key), "Listed in the manifest but the file is missing".to_string()));
+    }
+
+    Ok((records.into_values().collect(), issues))
+}
+
+async fn run_import(
+    state: &AppState,
+    job: &JobHandle,
+    mut endpoint: Endpoint,
+    path: String,
+    options: ImportOptions
+) -> Result<ImportSummary, ApiError> {
+    let parse_job = job.clone();
+    let (format, columns) = (options.format, options.csv_columns.clone().unwrap_or_default());
+    let entries = tauri::async_runtime::spawn_blocking(move || match format {
+        ImportFormat::BulkJson => parse_bulk_json(&path),
+        ImportFormat::Ndjson => parse_ndjson(&path, &parse_job),
+        ImportFormat::Csv => parse_csv(&path, &columns, &parse_job),
+    })
+    .await
+    .map_err(|e| format!("Failed to read import file: {}", e))??;
+
+    let (records, issues) = collect_records(entries);
+    let transfer_options = TransferOptions { mode: options.mode, dry_run: options.dry_run };
+    let transfer = apply_records(state, job, &mut endpoint, records, transfer_options).await?;
+
+    Ok(ImportSummary { transfer, issues })
+}
+
+#[command]
+pub async fn import_keys(
+    app: AppHandle,
+    namespace: NamespaceRef,
+    path: String,
+    options: ImportOptions,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let permission = if options.dry_run { KVPermission::Read } else { KVPermission::Write };
+    let endpoint = Endpoint::resolve(&state, &namespace, permission).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "import_keys", move |job| async move {
+        let state = task_app.state::<AppState>();
+        run_import(&state, &job, endpoint, path, options).await
+    });
+
+    Ok(job_id)
+}
+
+/// Keys with no file in the tree are left alone unless `prune` is set.
+#[command]
+pub async fn import_directory(
+    app: AppHandle,
+    namespace: NamespaceRef,
+    path: String,
+    options: DirectoryImportOptions,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let permission = if options.dry_run { KVPermission::Read } else { KVPermission::Write };
+    let mut endpoint = Endpoint::resolve(&state, &namespace, permission).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "import_directory", move |job| async move {
+        let state = task_app.state::<AppState>();
+        let read_job = job.clone();
+        let (records, issues) = tauri::async_runtime::spawn_blocking(move || read_directory(&path, &read_job))
+            .await
+            .map_err(|e| format!("Failed to read import folder: {}", e))??;
+
+        if options.prune && !options.dry_run && !issues.is_empty() {
+            return Err(format!("Resolve the {} problems in the folder before pruning keys", issues.len()).into());
+        }
+
+        let transfer_options = TransferOptions { mode: options.mode, dry_run: options.dry_run };
+        let transfer = if options.prune {
+            replace_records(&state, &job, &mut endpoint, records, transfer_options).await?
+        } else {
+            apply_records(&state, &job, &mut endpoint, records, transfer_options).await?
+        };
+        Ok::<_, ApiError>(ImportSummary { transfer, issues })
+    });
+
+    Ok(job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn csv_rows(data: &str) -> Vec<csv::StringRecord> {
+        csv::Reader::from_reader(data.as_bytes()).records().map(Result::unwrap).collect()
+    }
+
+    fn entry(key: &str, value: &str) -> Result<CloudflareBulkWrite, String> {
+        Ok(CloudflareBulkWrite {
+            key: key.to_string(),
+            value: value.to_string(),
+            expiration: None,
+            expiration_ttl: None,
+            metadata: None,
+            base64: false,
+        })
+    }
+
+    #[test]
+    fn parses_ndjson_lines() {
+        let entry = parse_ndjson_line(r#"{"key":"a","value":"aGk=","base64":true,"metadata":{"v":1}}"#).unwrap().unwrap();
+        assert_eq!(entry.key, "a");
+        assert!(entry.base64);
+        assert_eq!(entry.into_record(0).unwrap().value.bytes, b"hi");
+
+        assert!(parse_ndjson_line(r#"{"summary":{"exported":3}}"#).is_none());
+        assert!(parse_ndjson_line(r#"{"value":"no key"}"#).unwrap().is_err());
+        assert!(parse_ndjson_line("not json").unwrap().is_err());
+    }
+
+    #[test]
+    fn parses_csv_rows_by_column() {
+        let rows = csv_rows("meta,name,body,expires\n\"{\"\"a\"\":1}\",k1,\"x, y\",1700000000\n,k2,,\n");
+        let columns = [Some(1), Some(2), Some(0), Some(3)];
+
+        let first = parse_csv_row(&rows[0], &columns).unwrap();
+        assert_eq!((first.key.as_str(), first.value.as_str()), ("k1", "x, y"));
+        assert_eq!(first.metadata, Some(serde_json::json!({"a": 1})));
+        assert_eq!(first.expiration, Some(1_700_000_000));
+
+        let second = parse_csv_row(&rows[1], &columns).unwrap();
+        assert_eq!(second.value, "");
+        assert_eq!((second.metadata, second.expiration), (None, None));
+    }
+
+    #[test]
+    fn rejects_bad_csv_fields() {
+        let rows = csv_rows("key,value,metadata,expiration\n,v,,\nk,v,{oops,\nk,v,,soon\n");
+        let columns = [Some(0), Some(1), Some(2), Some(3)];
+
+        assert!(parse_csv_row(&rows[0], &columns).unwrap_err().contains("Key column"));
+        assert!(parse_csv_row(&rows[1], &columns).unwrap_err().contains("Metadata"));
+        assert!(parse_csv_row(&rows[2], &columns).unwrap_err().contains("Unix timestamp"));
+    }
+
+    #[test]
+    fn later_duplicates_replace_earlier_entries() {
+        let entries = vec![
+            (1, entry("a", "first")),
+            (2, Err("bad line".to_string())),
+            (3, entry("b", "only")),
+            (4, entry("a", "second")),
+        ];
+
+        let (records, issues) = collect_records(entries);
+        let values: Vec<(&str, &[u8])> = records.iter().map(|r| (r.key.as_str(), r.value.bytes.as_slice())).collect();
+        assert_eq!(values, vec![("a", b"second".as_slice()), ("b", b"only".as_slice())]);
+
+        let lines: Vec<usize> = issues.iter().map(|i| i.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
+}
@@ -0,0 +1,19 @@
+use tauri::{command, State};
+
+use crate::app_state::AppState;
+use crate::models::job::{JobId, JobInfo};
+
+#[command]
+pub fn list_jobs(state: State<AppState>) -> Vec<JobInfo> {
+    state.jobs.list()
+}
+
+#[command]
+pub fn get_job(job_id: JobId, state: State<AppState>) -> Result<JobInfo, String> {
+    state.jobs.get(job_id).ok_or_else(|| "Job not found".to_string())
+}
+
+#[command]
+pub fn cancel_job(job_id: JobId, state: State<AppState>) -> Result<(), String> {
+    state.jobs.cancel(job_id)
+}
@@ -1,11 +1,22 @@
 use std::fs;
 use std::path::{Path, PathBuf};
-use rusqlite::Connection;
+use std::time::UNIX_EPOCH;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use hmac::{Hmac, Mac};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde_json::Value;
-use tauri::{command, State};
+use sha2::{Digest, Sha256};
+use tauri::{command, AppHandle, State};
 
 use crate::app_state::AppState;
+use crate::jobs::JobHandle;
+use crate::models::job::JobId;
 use crate::models::kv::{KVEntry, KVNamespace, LocalFolder, LocalFolderInfo};
+use crate::models::transfer::KVRecord;
+use crate::models::value::KVValue;
+
+type EntryRow = (String, String, Option<i64>, Option<String>);
 
 fn extract_folder_name(path: &Path) -> String {
     path.file_name()
@@ -20,8 +31,16 @@ pub fn get_folders(state: State<AppState>) -> Vec<LocalFolderInfo> {
     folders.values().map(|f| f.into()).collect()
 }
 
+fn spawn_folder_load(app: &AppHandle, state: &AppState, path: PathBuf, folder_id: i64) -> JobId {
+    state.jobs.spawn(app, "load_folder", move |job| async move {
+        tauri::async_runtime::spawn_blocking(move || load_namespaces_for_folder(&path, folder_id, &job))
+            .await
+            .map_err(|e| format!("Failed to load folder: {}", e))?
+    })
+}
+
 #[command]
-pub fn add_folder(path: String, state: State<AppState>) -> Result<Vec<KVNamespace>, String> {
+pub fn add_folder(app: AppHandle, path: String, state: State<AppState>) -> Result<JobId, String> {
     let path = PathBuf::from(path);
     let folder_name = extract_folder_name(&path);
 
@@ -44,7 +63,7 @@ pub fn add_folder(path: String, state: State<AppState>) -> Result<Vec<KVNamespac
         folders.insert(folder_id, local_folder);
     }
 
-    load_namespaces_for_folder(&path, folder_id)
+    Ok(spawn_folder_load(&app, &state, path, folder_id))
 }
 
 #[command]
@@ -65,7 +84,7 @@ pub fn remove_folder(folder_id: i64, state: State<AppState>) -> Result<(), Strin
 }
 
 #[command]
-pub fn load_folder(folder_id: i64, state: State<AppState>) -> Result<Vec<KVNamespace>, String> {
+pub fn load_folder(app: AppHandle, folder_id: i64, state: State<AppState>) -> Result<JobId, String> {
     let folder_path = {
         let folders = state.folders.lock().unwrap();
         match folders.get(&folder_id) {
@@ -81,11 +100,280 @@ pub fn load_folder(folder_id: i64, state: State<AppState>) -> Result<Vec<KVNames
         }
     }
 
-    load_namespaces_for_folder(&folder_path, folder_id)
+    Ok(spawn_folder_load(&app, &state, folder_path, folder_id))
+}
+
+pub fn kv_storage_path(path: &Path) -> PathBuf {
+    path.join(".wrangler").join("state").join("v3").join("kv")
+}
+
+const ENTRIES_OBJECT: &str = "miniflare-KVNamespaceObject";
+
+/// Miniflare keeps each namespace in its own Durable Object, stored under the
+/// id workerd derives from the namespace name.
+fn entries_db_name(namespace_name: &str) -> String {
+    let key = Sha256::digest(ENTRIES_OBJECT.as_bytes());
+    let mac = |data: &[u8]| {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes()
+    };
+
+    let name_mac = mac(namespace_name.as_bytes());
+    let check = mac(&name_mac[..16]);
+    let id: String = name_mac[..16].iter().chain(&check[..16]).map(|b| format!("{:02x}", b)).collect();
+    format!("{}.sqlite", id)
+}
+
+fn find_entries_db(kv_path: &Path, namespace_name: &str) -> Option<PathBuf> {
+    let dir = kv_path.join(ENTRIES_OBJECT);
+    let path = dir.join(entries_db_name(namespace_name));
+    if path.is_file() {
+        return Some(path);
+    }
+
+    let mut databases = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().unwrap_or_default() == "sqlite");
+    match (databases.next(), databases.next()) {
+        (Some(only), None) => Some(only),
+        _ => None,
+    }
+}
+
+fn open_entries_db(kv_path: &Path, namespace_name: &str) -> Result<Connection, String> {
+    let db_path = find_entries_db(kv_path, namespace_name).ok_or_else(|| "SQLite database not found".to_string())?;
+    Connection::open(db_path).map_err(|_| "Failed to open SQLite database".to_string())
+}
+
+/// Maps a `folder-<id>-ns-<name>` namespace id to the folder's KV storage
+/// directory and the namespace directory name inside it.
+pub fn resolve_namespace(state: &AppState, folder_id: i64, namespace_id: &str) -> Result<(PathBuf, String), String> {
+    let namespace_name = if namespace_id.starts_with("folder-") {
+        namespace_id.splitn(4, '-').nth(3)
+            .ok_or_else(|| "Invalid namespace ID format".to_string())?
+    } else {
+        namespace_id
+    };
+
+    let folders = state.folders.lock().unwrap();
+    match folders.get(&folder_id) {
+        Some(folder) => Ok((kv_storage_path(&folder.path), namespace_name.to_string())),
+        None => Err("Folder not found".to_string()),
+    }
+}
+
+/// Reads every entry of a local namespace with its raw value bytes. Miniflare
+/// stores expirations in milliseconds; records carry them in seconds like the
+/// Cloudflare API does.
+pub fn read_local_records(kv_path: &Path, namespace_name: &str, job: &JobHandle) -> Result<Vec<KVRecord>, String> {
+    let conn = open_entries_db(kv_path, namespace_name)?;
+    let blob_path = kv_path.join(namespace_name).join("blobs");
+
+    let mut stmt = conn
+        .prepare("SELECT key, blob_id, expiration, metadata FROM _mf_entries ORDER BY key")
+        .map_err(|_| "Failed to prepare SQL statement".to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|_| "Failed to read local entries".to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Failed to read local entries".to_string())?;
+
+    let total = rows.len() as u64;
+    let mut records = Vec::with_capacity(rows.len());
+
+    for (index, row) in rows.into_iter().enumerate() {
+        job.ensure_active::<String>()?;
+
+        records.extend(load_record(&blob_path, row));
+        job.progress(index as u64 + 1, Some(total), Some("Reading local entries".to_string()));
+    }
+
+    Ok(records)
+}
+
+pub fn read_local_page(
+    kv_path: &Path,
+    namespace_name: &str,
+    after: Option<&str>,
+    prefix: Option<&str>,
+    limit: usize
+) -> Result<(Vec<KVRecord>, Option<String>), String> {
+    let conn = open_entries_db(kv_path, namespace_name)?;
+    let blob_path = kv_path.join(namespace_name).join("blobs");
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT key, blob_id, expiration, metadata FROM _mf_entries
+             WHERE key > ?1 AND substr(key, 1, length(?2)) = ?2
+             ORDER BY key LIMIT ?3"
+        )
+        .map_err(|_| "Failed to prepare SQL statement".to_string())?;
+
+    let rows = stmt
+        .query_map(params![after.unwrap_or(""), prefix.unwrap_or(""), limit as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|_| "Failed to read local entries".to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Failed to read local entries".to_string())?;
+
+    let next = if rows.len() == limit { rows.last().map(|row| row.0.clone()) } else { None };
+    Ok((rows.into_iter().filter_map(|row| load_record(&blob_path, row)).collect(), next))
+}
+
+fn load_record(blob_path: &Path, (key, blob_id, expiration, metadata): EntryRow) -> Option<KVRecord> {
+    let blob_file = blob_path.join(&blob_id);
+    let bytes = fs::read(&blob_file).ok()?;
+    let modified_at = fs::metadata(&blob_file)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp());
+
+    Some(KVRecord {
+        key,
+        value: KVValue::from_bytes(bytes),
+        expiration: expiration.map(|ms| ms / 1000),
+        metadata: metadata.and_then(|m| serde_json::from_str(&m).ok()),
+        modified_at,
+    })
+}
+
+pub fn local_namespace_names(kv_path: &Path) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(kv_path).map_err(|_| "Failed to read KV directory".to_string())?;
+
+    Ok(entries
+        .flatten()
+        .filter(|entry| entry.metadata().is_ok_and(|m| m.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with("miniflare-"))
+        .collect())
 }
 
-fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64) -> Result<Vec<KVNamespace>, String> {
-    let kv_path = path.join(".wrangler").join("state").join("v3").join("kv");
+/// Includes the `-wal` and `-shm` files, which change before the database itself does.
+pub fn entries_modified_at(kv_path: &Path, namespace_name: &str) -> Option<i64> {
+    let db_path = find_entries_db(kv_path, namespace_name)?;
+    let prefix = db_path.file_name()?.to_string_lossy().to_string();
+
+    fs::read_dir(db_path.parent()?)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as i64)
+}
+
+/// Miniflare writes a new blob for each change, so the blob id doubles as a revision.
+pub fn read_local_revisions(kv_path: &Path, namespace_name: &str) -> Result<Vec<(String, String)>, String> {
+    let conn = open_entries_db(kv_path, namespace_name)?;
+    let mut stmt = conn
+        .prepare("SELECT key, blob_id FROM _mf_entries")
+        .map_err(|_| "Failed to prepare SQL statement".to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|_| "Failed to read local entries".to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Failed to read local entries".to_string());
+    rows
+}
+
+pub fn read_local_blob(kv_path: &Path, namespace_name: &str, blob_id: &str) -> Option<Vec<u8>> {
+    fs::read(kv_path.join(namespace_name).join("blobs").join(blob_id)).ok()
+}
+
+/// Same shape as Miniflare's blob ids: 32 random bytes and a timestamp, hex encoded.
+fn generate_blob_id() -> String {
+    let mut id = [0u8; 40];
+    OsRng.fill_bytes(&mut id[..32]);
+    id[32..].copy_from_slice(&(chrono::Utc::now().timestamp_micros() as u64).to_be_bytes());
+    id.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Replaced blobs are only removed once the transaction has committed.
+pub fn write_local_records(kv_path: &Path, namespace_name: &str, records: &[KVRecord], job: &JobHandle) -> Result<usize, String> {
+    let conn = open_entries_db(kv_path, namespace_name)?;
+    let blob_path = kv_path.join(namespace_name).join("blobs");
+    fs::create_dir_all(&blob_path).map_err(|_| "Failed to create blob directory".to_string())?;
+
+    conn.execute("BEGIN TRANSACTION", []).map_err(|_| "Failed to start transaction".to_string())?;
+
+    let mut written_blobs = Vec::new();
+    let mut replaced_blobs = Vec::new();
+    let total = records.len() as u64;
+
+    let outcome = records.iter().enumerate().try_for_each(|(index, record)| {
+        job.ensure_active::<String>()?;
+
+        let previous: Option<String> = conn
+            .query_row("SELECT blob_id FROM _mf_entries WHERE key = ?", [&record.key], |row| row.get(0))
+            .optional()
+            .map_err(|_| "Failed to read local entries".to_string())?;
+
+        let blob_id = generate_blob_id();
+        fs::write(blob_path.join(&blob_id), &record.value.bytes)
+            .map_err(|_| format!("Failed to write blob file for key: {}", record.key))?;
+        written_blobs.push(blob_id.clone());
+
+        conn.execute(
+            "INSERT INTO _mf_entries (key, blob_id, expiration, metadata) VALUES (?, ?, ?, ?)
+             ON CONFLICT(key) DO UPDATE SET
+                blob_id = excluded.blob_id,
+                expiration = excluded.expiration,
+                metadata = excluded.metadata",
+            params![
+                record.key,
+                blob_id,
+                record.expiration.map(|seconds| seconds * 1000),
+                record.metadata.as_ref().map(|m| m.to_string())
+            ],
+        ).map_err(|_| format!("Failed to write key: {}", record.key))?;
+
+        replaced_blobs.extend(previous);
+        job.progress(index as u64 + 1, Some(total), Some("Writing local entries".to_string()));
+        Ok::<_, String>(())
+    });
+
+    let outcome = outcome.and_then(|_| {
+        conn.execute("COMMIT", []).map(|_| ()).map_err(|_| "Failed to commit transaction".to_string())
+    });
+
+    match outcome {
+        Ok(()) => {
+            for blob_id in replaced_blobs {
+                fs::remove_file(blob_path.join(blob_id)).ok();
+            }
+            Ok(records.len())
+        }
+        Err(e) => {
+            conn.execute("ROLLBACK", []).ok();
+            for blob_id in written_blobs {
+                fs::remove_file(blob_path.join(blob_id)).ok();
+            }
+            Err(e)
+        }
+    }
+}
+
+fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64, job: &JobHandle) -> Result<Vec<KVNamespace>, String> {
+    let kv_path = kv_storage_path(path);
 
     if !kv_path.exists() {
         return Err("No Wrangler KV storage found at this location".to_string());
@@ -94,11 +382,15 @@ fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64) -> Result<Vec<KVNa
     let mut namespaces = Vec::new();
 
     let entries = match fs::read_dir(&kv_path) {
-        Ok(entries) => entries,
+        Ok(entries) => entries.collect::<Vec<_>>(),
         Err(_) => return Err("Failed to read KV directory".to_string()),
     };
+    let total = entries.len() as u64;
+
+    for (index, entry) in entries.into_iter().enumerate() {
+        job.ensure_active::<String>()?;
+        job.progress(index as u64, Some(total), None);
 
-    for entry in entries {
         let entry = match entry {
             Ok(entry) => entry,
             Err(_) => continue,
@@ -118,25 +410,7 @@ fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64) -> Result<Vec<KVNa
         let namespace_dir = entry.path();
         let blob_path = namespace_dir.join("blobs");
 
-        let db_dir = kv_path.join("miniflare-KVNamespaceObject");
-        let mut db_path = None;
-
-        if let Ok(db_entries) = fs::read_dir(&db_dir) {
-            for db_entry in db_entries {
-                if let Ok(db_entry) = db_entry {
-                    if db_entry.path().extension().unwrap_or_default() == "sqlite" {
-                        db_path = Some(db_entry.path());
-                        break;
-                    }
-                }
-            }
-        }
-
-        if db_path.is_none() {
-            continue;
-        }
-
-        let conn = match Connection::open(db_path.unwrap()) {
+        let conn = match open_entries_db(&kv_path, &namespace_name) {
             Ok(conn) => conn,
             Err(_) => continue,
         };
@@ -197,7 +471,8 @@ fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64) -> Result<Vec<KVNa
             r#type: "local".to_string(),
             account_id: None,
             folder_id: Some(folder_id),
-            count: Some(entries_count)
+            count: Some(entries_count),
+            synced_at: None,
         });
     }
 
@@ -206,46 +481,10 @@ fn load_namespaces_for_folder(path: &PathBuf, folder_id: i64) -> Result<Vec<KVNa
 
 #[command]
 pub fn update_kv(folder_id: i64, namespace_id: String, key: String, value_str: String, state: State<AppState>) -> Result<(), String> {
-    let namespace_name = if namespace_id.starts_with("folder-") {
-        namespace_id.splitn(4, "-").nth(3)
-            .ok_or_else(|| "Invalid namespace ID format".to_string())?
-    } else {
-        &namespace_id
-    };
-
-    let path = {
-        let folders = state.folders.lock().unwrap();
-        match folders.get(&folder_id) {
-            Some(folder) => folder.path.clone(),
-            None => return Err("Folder not found".to_string())
-        }
-    };
-
-    let kv_path = path.join(".wrangler").join("state").join("v3").join("kv");
+    let (kv_path, namespace_name) = resolve_namespace(&state, folder_id, &namespace_id)?;
     let namespace_path = kv_path.join(&namespace_name).join("blobs");
 
-    let db_dir = kv_path.join("miniflare-KVNamespaceObject");
-    let mut db_path = None;
-
-    if let Ok(db_entries) = fs::read_dir(&db_dir) {
-        for db_entry in db_entries {
-            if let Ok(db_entry) = db_entry {
-                if db_entry.path().extension().unwrap_or_default() == "sqlite" {
-                    db_path = Some(db_entry.path());
-                    break;
-                }
-            }
-        }
-    }
-
-    if db_path.is_none() {
-        return Err("SQLite database not found".to_string());
-    }
-
-    let conn = match Connection::open(db_path.unwrap()) {
-        Ok(conn) => conn,
-        Err(_) => return Err("Failed to open SQLite database".to_string()),
-    };
+    let conn = open_entries_db(&kv_path, &namespace_name)?;
 
     let mut stmt = match conn.prepare("SELECT blob_id FROM _mf_entries WHERE key = ?") {
         Ok(stmt) => stmt,
@@ -275,53 +514,21 @@ pub fn update_kv(folder_id: i64, namespace_id: String, key: String, value_str: S
 
 #[command]
 pub fn delete_kv(folder_id: i64, namespace_id: String, keys: Vec<String>, state: State<AppState>) -> Result<(), String> {
-    let namespace_name = if namespace_id.starts_with("folder-") {
-        namespace_id.splitn(4, "-").nth(3)
-            .ok_or_else(|| "Invalid namespace ID format".to_string())?
-    } else {
-        &namespace_id
-    };
-
-    let path = {
-        let folders = state.folders.lock().unwrap();
-        match folders.get(&folder_id) {
-            Some(folder) => folder.path.clone(),
-            None => return Err("Folder not found".to_string())
-        }
-    };
-
-    let kv_path = path.join(".wrangler").join("state").join("v3").join("kv");
-    let namespace_path = kv_path.join(&namespace_name).join("blobs");
-
-    let db_dir = kv_path.join("miniflare-KVNamespaceObject");
-    let mut db_path = None;
-
-    if let Ok(db_entries) = fs::read_dir(&db_dir) {
-        for db_entry in db_entries {
-            if let Ok(db_entry) = db_entry {
-                if db_entry.path().extension().unwrap_or_default() == "sqlite" {
-                    db_path = Some(db_entry.path());
-                    break;
-                }
-            }
-        }
-    }
+    let (kv_path, namespace_name) = resolve_namespace(&state, folder_id, &namespace_id)?;
+    delete_local_keys(&kv_path, &namespace_name, &keys)
+}
 
-    if db_path.is_none() {
-        return Err("SQLite database not found".to_string());
-    }
+pub fn delete_local_keys(kv_path: &Path, namespace_name: &str, keys: &[String]) -> Result<(), String> {
+    let namespace_path = kv_path.join(namespace_name).join("blobs");
 
-    let conn = match Connection::open(db_path.unwrap()) {
-        Ok(conn) => conn,
-        Err(_) => return Err("Failed to open SQLite database".to_string()),
-    };
+    let conn = open_entries_db(kv_path, namespace_name)?;
 
     match conn.execute("BEGIN TRANSACTION", []) {
         Ok(_) => {},
         Err(_) => return Err("Failed to start transaction".to_string()),
     }
 
-    for key in &keys {
+    for key in keys {
         let mut stmt = match conn.prepare("SELECT blob_id FROM _mf_entries WHERE key = ?") {
             Ok(stmt) => stmt,
             Err(_) => {
@@ -370,4 +577,26 @@ pub fn delete_kv(folder_id: i64, namespace_id: String, keys: Vec<String>, state:
             Err("Failed to commit transaction".to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_entries_database_of_each_namespace() {
+        let kv_path = std::env::temp_dir().join(format!("kv-entries-{}", std::process::id()));
+        let dir = kv_path.join(ENTRIES_OBJECT);
+        fs::create_dir_all(&dir).unwrap();
+
+        fs::write(dir.join(entries_db_name("first")), b"").unwrap();
+        assert_eq!(find_entries_db(&kv_path, "second"), Some(dir.join(entries_db_name("first"))));
+
+        fs::write(dir.join(entries_db_name("second")), b"").unwrap();
+        assert_eq!(find_entries_db(&kv_path, "first"), Some(dir.join(entries_db_name("first"))));
+        assert_eq!(find_entries_db(&kv_path, "second"), Some(dir.join(entries_db_name("second"))));
+        assert_eq!(find_entries_db(&kv_path, "third"), None);
+
+        fs::remove_dir_all(&kv_path).unwrap();
+    }
+}
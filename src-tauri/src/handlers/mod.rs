@@ -1,2 +1,11 @@
+pub mod cache;
+pub mod diff;
+pub mod export;
+pub mod import;
+pub mod jobs;
 pub mod local;
-pub mod remote;
\ No newline at end of file
+pub mod remote;
+pub mod search;
+pub mod sync;
+pub mod transfer;
+pub mod vault;
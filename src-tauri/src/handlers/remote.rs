@@ -1,162 +1,1190 @@
-use reqwest::{Client, header};
+use std::collections::HashMap;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, header};
 use serde_json::Value;
-use tauri::{command, State};
+use tauri::{command, AppHandle, Manager, State};
 
 use crate::app_state::AppState;
-use crate::models::kv::{KVEntry, KVNamespace, RemoteConnection};
-use crate::models::cloudflare::{CloudflareListResponse, CloudflareNamespace, CloudflareKey, CloudflareKeysResponse};
+use crate::credentials;
+use crate::rate_limit;
+use crate::jobs::JobHandle;
+use crate::handlers::cache::{
+    cached_fetch, forget_cached, invalidate_cached_keys, is_offline, read_cached, require_online, set_offline, store_cached
+};
+use crate::models::cache::CacheKey;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::kv::{
+    AccountError, ApiAuth, CredentialSource, ExternalConnections, KVEntry, KVNamespace, KVPermission, RateLimitBudget,
+    RemoteConnection, RemoteConnectionInfo, RemoteNamespaces, TokenPermissions, REAUTH_FAILURE_THRESHOLD
+};
+use crate::models::transfer::{KVRecord, KeyInfo};
+use crate::models::value::{fingerprint, BulkMetadataResult, BulkValueResult, KVValue, RemoteValueSnapshot, ValueKind};
+use crate::models::cloudflare::{
+    CloudflareAccount, CloudflareBulkGetResult, CloudflareBulkWrite, CloudflareBulkWriteResult, CloudflareError, CloudflareErrorEnvelope, CloudflareListResponse, CloudflareNamespace, CloudflareKey, CloudflareKeysResponse, CloudflareResponse,
+    CloudflareToken, CloudflareTokenPolicy, CloudflareTokenStatus
+};
 
-#[command]
-pub async fn connect_cloudflare(account_id: String, api_token: String, state: State<'_, AppState>) -> Result<(), String> {
+const BULK_GET_LIMIT: usize = 100;
+const BULK_DELETE_LIMIT: usize = 10000;
+const BULK_WRITE_LIMIT: usize = 10000;
+const BULK_WRITE_MAX_BYTES: usize = 90 * 1024 * 1024;
+const MAX_CONCURRENT_REQUESTS: usize = 6;
+
+trait WithAuth {
+    fn with_auth(self, auth: &ApiAuth) -> Self;
+}
+
+impl WithAuth for RequestBuilder {
+    fn with_auth(self, auth: &ApiAuth) -> Self {
+        rate_limit::record(auth);
+
+        match auth {
+            ApiAuth::Bearer { token } => self.header(header::AUTHORIZATION, format!("Bearer {}", token)),
+            ApiAuth::GlobalKey { email, key } => self.header("X-Auth-Email", email).header("X-Auth-Key", key),
+        }
+    }
+}
+
+fn request_error(e: reqwest::Error) -> ApiError {
+    if e.is_connect() || e.is_timeout() {
+        ApiError::NetworkUnavailable { message: format!("Network unavailable: {}", e) }
+    } else {
+        format!("API request failed: {}", e).into()
+    }
+}
+
+async fn check_response(response: Response) -> Result<Response, ApiError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status().as_u16();
+    let retry_after = response.headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok());
+    let errors = response.json::<CloudflareErrorEnvelope>().await
+        .map(|envelope| envelope.errors)
+        .unwrap_or_default();
+
+    Err(ApiError::from_response(status, &errors, retry_after))
+}
+
+fn envelope_error(errors: &[CloudflareError]) -> ApiError {
+    ApiError::from_response(StatusCode::OK.as_u16(), errors, None)
+}
+
+fn encode_key(key_name: &str) -> String {
+    utf8_percent_encode(key_name, NON_ALPHANUMERIC).to_string()
+}
+
+async fn fetch_remote_metadata(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    key_name: &str
+) -> Result<Option<Value>, ApiError> {
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/metadata/{}",
+        connection.account_id, namespace_id, encode_key(key_name)
+    );
+
+    let response = client
+        .get(&url)
+        .with_auth(&connection.auth)
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let response = check_response(response).await?;
+
+    let response_data: CloudflareResponse<Value> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    if !response_data.success {
+        return Err(envelope_error(&response_data.errors));
+    }
+
+    Ok(response_data.result.filter(|metadata| !metadata.is_null()))
+}
+
+const KV_READ_PERMISSION: &str = "Workers KV Storage Read";
+const KV_WRITE_PERMISSION: &str = "Workers KV Storage Write";
+
+async fn fetch_token_policies(client: &Client, token_id: &str, api_token: &str) -> Option<Vec<CloudflareTokenPolicy>> {
+    let url = format!("https://api.cloudflare.com/client/v4/user/tokens/{}", token_id);
+
+    let response = client
+        .get(&url)
+        .with_auth(&ApiAuth::Bearer { token: api_token.to_string() })
+        .send()
+        .await
+        .ok()?;
+
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let response_data: CloudflareResponse<CloudflareToken> = response.json().await.ok()?;
+    response_data.result.map(|token| token.policies)
+}
+
+fn policy_permissions(policies: &[CloudflareTokenPolicy], account_id: &str, effect: &str) -> Vec<String> {
+    let account_resource = format!("com.cloudflare.api.account.{}", account_id);
+
+    policies
+        .iter()
+        .filter(|p| p.effect == effect)
+        .filter(|p| p.resources.contains_key(&account_resource) || p.resources.contains_key("com.cloudflare.api.account.*"))
+        .flat_map(|p| p.permission_groups.iter().map(|g| g.name.clone()))
+        .collect()
+}
+
+async fn verify_token_status(client: &Client, api_token: &str) -> Result<String, ApiError> {
+    let response = client
+        .get("https://api.cloudflare.com/client/v4/user/tokens/verify")
+        .with_auth(&ApiAuth::Bearer { token: api_token.to_string() })
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let response = check_response(response).await?;
+
+    let response_data: CloudflareResponse<CloudflareTokenStatus> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    let token = response_data.result
+        .ok_or_else(|| "API token verification returned no result".to_string())?;
+
+    if token.status != "active" {
+        return Err(ApiError::InvalidAuth { message: format!("API token is {}", token.status) });
+    }
+
+    Ok(token.id)
+}
+
+async fn verify_credentials(account_id: &str, auth: &ApiAuth) -> Result<TokenPermissions, ApiError> {
     let client = Client::new();
+
+    let token_id = match auth {
+        ApiAuth::Bearer { token } => Some(verify_token_status(&client, token).await?),
+        ApiAuth::GlobalKey { .. } => None,
+    };
+
     let url = format!("https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces", account_id);
 
     let response = client
         .get(&url)
-        .header(header::AUTHORIZATION, format!("Bearer {}", api_token))
+        .with_auth(auth)
         .send()
         .await
-        .map_err(|e| format!("API request failed: {}", e))?;
+        .map_err(request_error)?;
+
+    check_response(response).await?;
+
+    let mut permissions = TokenPermissions {
+        read: Some(true),
+        ..TokenPermissions::default()
+    };
+
+    match (auth, token_id) {
+        (ApiAuth::Bearer { token }, Some(token_id)) => {
+            // Tokens without "API Tokens Read" cannot see their own policies; write access then stays
+            // unknown and `require_permission` refuses writes.
+            if let Some(policies) = fetch_token_policies(&client, &token_id, token).await {
+                let allowed = policy_permissions(&policies, account_id, "allow");
+                let denied = policy_permissions(&policies, account_id, "deny");
+                let can_write = allowed.iter().any(|p| p == KV_WRITE_PERMISSION)
+                    && !denied.iter().any(|p| p == KV_WRITE_PERMISSION);
+
+                // KV namespace create, rename and delete are covered by the same write permission group.
+                permissions.write = Some(can_write);
+                permissions.manage_namespaces = Some(can_write);
+            }
+        }
+        _ => {
+            // A Global API Key acts with every permission of the user who owns it.
+            permissions.write = Some(true);
+            permissions.manage_namespaces = Some(true);
+        }
+    }
+
+    Ok(permissions)
+}
+
+pub fn require_permission(connection: &RemoteConnection, permission: KVPermission) -> Result<(), ApiError> {
+    let (action, permission_group) = match permission {
+        KVPermission::Read => ("read KV data", KV_READ_PERMISSION),
+        KVPermission::Write => ("write KV values", KV_WRITE_PERMISSION),
+        KVPermission::ManageNamespaces => ("manage KV namespaces", KV_WRITE_PERMISSION),
+    };
+
+    match (connection.permissions.allows(permission), permission) {
+        (Some(true), _) | (None, KVPermission::Read) => Ok(()),
+        (None, _) => Err(ApiError::PermissionUnknown {
+            message: format!(
+                "Could not confirm that the API token for {} is allowed to {}. Add the \"API Tokens Read\" permission to the token or replace it with one that has \"{}\", then reconnect.",
+                connection.label, action, permission_group
+            ),
+        }),
+        (Some(false), _) => Err(ApiError::PermissionDenied {
+            message: format!(
+                "The API token for {} is not allowed to {}. Replace it with a token that has the \"{}\" permission.",
+                connection.label, action, permission_group
+            ),
+        }),
+    }
+}
+
+async fn resolve_credentials(connection: &mut RemoteConnection) -> Result<(), ApiError> {
+    match connection.source {
+        CredentialSource::Manual => {}
+        CredentialSource::Wrangler => {
+            connection.auth = ApiAuth::Bearer {
+                token: credentials::current_wrangler_login().await?.oauth_token,
+            };
+        }
+        CredentialSource::Environment => {
+            connection.auth = credentials::environment_credentials()
+                .map(|c| ApiAuth::Bearer { token: c.api_token })
+                .ok_or_else(|| "CLOUDFLARE_API_TOKEN is no longer set".to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn find_connection(state: &AppState, account_id: &str) -> Result<RemoteConnection, ApiError> {
+    let connections = state.remote_connections.lock().unwrap();
+    connections
+        .iter()
+        .find(|c| c.account_id == account_id)
+        .cloned()
+        .ok_or_else(|| "Connection not found".into())
+}
+
+pub async fn connection_for_account(state: &AppState, account_id: &str) -> Result<RemoteConnection, ApiError> {
+    let mut connection = find_connection(state, account_id)?;
+
+    resolve_credentials(&mut connection).await?;
+
+    Ok(connection)
+}
+
+fn store_connection(state: &AppState, mut connection: RemoteConnection) -> Result<RemoteConnectionInfo, ApiError> {
+    connection.id = {
+        let db = state.db.lock().unwrap();
+        match db.save_remote_connection(&connection) {
+            Ok(id) => id,
+            Err(e) => return Err(format!("Failed to save connection: {}", e).into()),
+        }
+    };
+
+    let mut connections = state.remote_connections.lock().unwrap();
+    match connections.iter_mut().find(|c| c.id == connection.id) {
+        Some(existing) => {
+            existing.label = connection.label;
+            existing.auth = connection.auth;
+            existing.permissions = connection.permissions;
+            existing.source = connection.source;
+            existing.failure_count = 0;
+            if connection.account_name.is_some() {
+                existing.account_name = connection.account_name;
+            }
+            Ok(RemoteConnectionInfo::from(&*existing))
+        }
+        None => {
+            let info = RemoteConnectionInfo::from(&connection);
+            connections.push(connection);
+            Ok(info)
+        }
+    }
+}
+
+async fn list_token_accounts(auth: &ApiAuth) -> Result<Vec<CloudflareAccount>, ApiError> {
+    let client = Client::new();
+    let mut accounts = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let url = format!("https://api.cloudflare.com/client/v4/accounts?page={}&per_page=50", page);
+
+        let response = client
+            .get(&url)
+            .with_auth(auth)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let response = check_response(response).await?;
+
+        let response_data: CloudflareListResponse<CloudflareAccount> = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+        if !response_data.success {
+            return Err(envelope_error(&response_data.errors));
+        }
+
+        accounts.extend(response_data.result);
+
+        let total_pages = response_data.result_info
+            .as_ref()
+            .and_then(|info| info.total_pages)
+            .unwrap_or(1);
+        if page >= total_pages {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(accounts)
+}
+
+#[command]
+pub async fn connect_cloudflare(
+    account_id: String,
+    api_token: String,
+    auth_email: Option<String>,
+    label: Option<String>,
+    state: State<'_, AppState>
+) -> Result<RemoteConnectionInfo, ApiError> {
+    let auth = ApiAuth::new(api_token, auth_email);
+    let permissions = verify_credentials(&account_id, &auth).await?;
+
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| account_id.clone());
+
+    store_connection(&state, RemoteConnection {
+        id: 0,
+        account_id,
+        account_name: None,
+        label,
+        auth,
+        permissions,
+        source: CredentialSource::Manual,
+        failure_count: 0,
+    })
+}
+
+#[command]
+pub async fn discover_cloudflare_accounts(api_token: String, auth_email: Option<String>) -> Result<Vec<CloudflareAccount>, ApiError> {
+    let accounts = list_token_accounts(&ApiAuth::new(api_token, auth_email)).await?;
+    if accounts.is_empty() {
+        return Err("These credentials cannot access any accounts".into());
+    }
+
+    Ok(accounts)
+}
+
+#[command]
+pub async fn connect_cloudflare_accounts(
+    api_token: String,
+    auth_email: Option<String>,
+    account_ids: Vec<String>,
+    state: State<'_, AppState>
+) -> Result<Vec<RemoteConnectionInfo>, ApiError> {
+    let auth = ApiAuth::new(api_token, auth_email);
+    let accounts = list_token_accounts(&auth).await?;
+    let mut connected = Vec::new();
+
+    for account_id in account_ids {
+        let account = accounts
+            .iter()
+            .find(|a| a.id == account_id)
+            .ok_or_else(|| format!("Account {} is not reachable with these credentials", account_id))?;
+
+        let permissions = verify_credentials(&account.id, &auth).await?;
+
+        connected.push(store_connection(&state, RemoteConnection {
+            id: 0,
+            account_id: account.id.clone(),
+            account_name: Some(account.name.clone()),
+            label: account.name.clone(),
+            auth: auth.clone(),
+            permissions,
+            source: CredentialSource::Manual,
+            failure_count: 0,
+        })?);
+    }
+
+    Ok(connected)
+}
+
+fn has_manual_connection(state: &AppState, account_id: &str) -> bool {
+    let connections = state.remote_connections.lock().unwrap();
+    connections
+        .iter()
+        .any(|c| c.account_id == account_id && c.source == CredentialSource::Manual)
+}
+
+fn source_error(label: &str, account_id: &str, error: ApiError) -> AccountError {
+    AccountError {
+        account_id: account_id.to_string(),
+        label: label.to_string(),
+        needs_reauth: matches!(error, ApiError::InvalidAuth { .. }),
+        error,
+    }
+}
+
+#[command]
+pub async fn load_external_connections(state: State<'_, AppState>) -> Result<ExternalConnections, ApiError> {
+    let mut loaded = Vec::new();
+    let mut errors = Vec::new();
+
+    if let Some(environment) = credentials::environment_credentials() {
+        let auth = ApiAuth::Bearer { token: environment.api_token };
+        let accounts = match environment.account_id {
+            Some(account_id) => Ok(vec![CloudflareAccount { name: account_id.clone(), id: account_id }]),
+            None => list_token_accounts(&auth).await,
+        };
+
+        match accounts {
+            Ok(accounts) => {
+                for account in accounts {
+                    if has_manual_connection(&state, &account.id) {
+                        continue;
+                    }
+
+                    let label = format!("{} (environment)", account.name);
+                    let stored = verify_credentials(&account.id, &auth).await.and_then(|permissions| {
+                        store_connection(&state, RemoteConnection {
+                            id: 0,
+                            label: label.clone(),
+                            account_id: account.id.clone(),
+                            account_name: Some(account.name),
+                            auth: ApiAuth::Bearer { token: String::new() },
+                            permissions,
+                            source: CredentialSource::Environment,
+                            failure_count: 0,
+                        })
+                    });
+
+                    match stored {
+                        Ok(connection) => loaded.push(connection),
+                        Err(error) => errors.push(source_error(&label, &account.id, error)),
+                    }
+                }
+            }
+            Err(error) => errors.push(source_error("CLOUDFLARE_API_TOKEN", "", error)),
+        }
+    }
+
+    if let Ok(login) = credentials::current_wrangler_login().await {
+        let permissions = credentials::wrangler_permissions(&login);
+
+        let auth = ApiAuth::Bearer { token: login.oauth_token.clone() };
+
+        match list_token_accounts(&auth).await {
+            Ok(accounts) => {
+                for account in accounts {
+                    if has_manual_connection(&state, &account.id)
+                        || loaded.iter().any(|c: &RemoteConnectionInfo| c.account_id == account.id)
+                    {
+                        continue;
+                    }
+
+                    let label = format!("{} (wrangler)", account.name);
+                    let stored = store_connection(&state, RemoteConnection {
+                        id: 0,
+                        label: label.clone(),
+                        account_id: account.id.clone(),
+                        account_name: Some(account.name),
+                        auth: ApiAuth::Bearer { token: String::new() },
+                        permissions: permissions.clone(),
+                        source: CredentialSource::Wrangler,
+                        failure_count: 0,
+                    });
+
+                    match stored {
+                        Ok(connection) => loaded.push(connection),
+                        Err(error) => errors.push(source_error(&label, &account.id, error)),
+                    }
+                }
+            }
+            Err(error) => errors.push(source_error("wrangler", "", error)),
+        }
+    }
+
+    Ok(ExternalConnections { connections: loaded, errors })
+}
+
+#[command]
+pub fn list_remote_connections(state: State<AppState>) -> Vec<RemoteConnectionInfo> {
+    let connections = state.remote_connections.lock().unwrap();
+    connections.iter().map(|c| c.into()).collect()
+}
+
+#[command]
+pub fn rename_remote_connection(connection_id: i64, label: String, state: State<AppState>) -> Result<RemoteConnectionInfo, ApiError> {
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        return Err("Label cannot be empty".into());
+    }
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.rename_connection(connection_id, &label) {
+            return Err(format!("Failed to rename connection: {}", e).into());
+        }
+    }
+
+    let mut connections = state.remote_connections.lock().unwrap();
+    let connection = connections
+        .iter_mut()
+        .find(|c| c.id == connection_id)
+        .ok_or_else(|| "Connection not found".to_string())?;
+    connection.label = label;
+
+    Ok(RemoteConnectionInfo::from(&*connection))
+}
+
+#[command]
+pub async fn replace_remote_connection_token(
+    connection_id: i64,
+    api_token: String,
+    auth_email: Option<String>,
+    state: State<'_, AppState>
+) -> Result<(), ApiError> {
+    let account_id = {
+        let connections = state.remote_connections.lock().unwrap();
+        connections
+            .iter()
+            .find(|c| c.id == connection_id)
+            .map(|c| c.account_id.clone())
+            .ok_or_else(|| "Connection not found".to_string())?
+    };
+
+    let auth = ApiAuth::new(api_token, auth_email);
+    let permissions = verify_credentials(&account_id, &auth).await?;
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_connection_auth(connection_id, &auth, &permissions) {
+            return Err(format!("Failed to update connection token: {}", e).into());
+        }
+    }
+
+    {
+        let mut connections = state.remote_connections.lock().unwrap();
+        if let Some(connection) = connections.iter_mut().find(|c| c.id == connection_id) {
+            connection.auth = auth;
+            connection.permissions = permissions;
+            connection.source = CredentialSource::Manual;
+            connection.failure_count = 0;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+pub fn disconnect_remote_connection(connection_id: i64, state: State<AppState>) -> Result<(), ApiError> {
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.remove_connection(connection_id) {
+            return Err(format!("Failed to remove connection from database: {}", e).into());
+        }
+    }
+
+    let removed = {
+        let mut connections = state.remote_connections.lock().unwrap();
+        let removed = connections.iter().find(|c| c.id == connection_id).map(|c| c.account_id.clone());
+        connections.retain(|c| c.id != connection_id);
+        removed
+    };
+
+    if let Some(account_id) = removed {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.clear_cache(Some(&account_id)) {
+            eprintln!("Failed to clear cache: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_namespaces(client: &Client, connection: &RemoteConnection) -> Result<Vec<CloudflareNamespace>, ApiError> {
+    let namespaces_url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces",
+        connection.account_id
+    );
+
+    let namespaces_response = client
+        .get(&namespaces_url)
+        .with_auth(&connection.auth)
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let namespaces_response = check_response(namespaces_response).await?;
+
+    let namespaces_data: CloudflareListResponse<CloudflareNamespace> = namespaces_response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    if !namespaces_data.success {
+        return Err(envelope_error(&namespaces_data.errors));
+    }
+
+    Ok(namespaces_data.result)
+}
+
+pub async fn create_remote_namespace(
+    state: &AppState,
+    connection: &RemoteConnection,
+    title: &str
+) -> Result<CloudflareNamespace, ApiError> {
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces",
+        connection.account_id
+    );
+
+    let response = Client::new()
+        .post(&url)
+        .with_auth(&connection.auth)
+        .json(&serde_json::json!({ "title": title }))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let response = check_response(response).await?;
+
+    let response_data: CloudflareResponse<CloudflareNamespace> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    if !response_data.success {
+        return Err(envelope_error(&response_data.errors));
+    }
+
+    forget_cached(state, &CacheKey::namespaces(&connection.account_id));
+
+    response_data.result.ok_or_else(|| "Cloudflare did not return the new namespace".into())
+}
+
+#[command]
+pub fn get_rate_limit_budgets(state: State<AppState>) -> Vec<RateLimitBudget> {
+    let connections = state.remote_connections.lock().unwrap();
+
+    connections
+        .iter()
+        .map(|connection| {
+            let budget = rate_limit::budget(&connection.auth);
+            RateLimitBudget {
+                account_id: connection.account_id.clone(),
+                label: connection.label.clone(),
+                limit: rate_limit::REQUEST_LIMIT,
+                used: budget.used,
+                remaining: budget.remaining,
+                window_seconds: rate_limit::WINDOW.as_secs(),
+                resets_in_seconds: budget.resets_in.as_secs(),
+            }
+        })
+        .collect()
+}
+
+fn note_connection_success(state: &AppState, connection: &RemoteConnection) {
+    if connection.failure_count == 0 {
+        return;
+    }
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.reset_connection_failures(connection.id) {
+            eprintln!("Failed to reset connection failures: {}", e);
+        }
+    }
+
+    let mut connections = state.remote_connections.lock().unwrap();
+    if let Some(existing) = connections.iter_mut().find(|c| c.id == connection.id) {
+        existing.failure_count = 0;
+    }
+}
+
+/// Counts a failed request against the connection and reports whether it now
+/// needs new credentials. Network and rate limit errors say nothing about the
+/// credentials, so they are not counted.
+fn note_connection_failure(state: &AppState, connection: &RemoteConnection, error: &ApiError) -> bool {
+    if error.is_network() || matches!(error, ApiError::RateLimited { .. }) {
+        return connection.needs_reauth();
+    }
+
+    let failure_count = {
+        let db = state.db.lock().unwrap();
+        match db.record_connection_failure(connection.id) {
+            Ok(count) => count,
+            Err(e) => {
+                eprintln!("Failed to record connection failure: {}", e);
+                connection.failure_count + 1
+            }
+        }
+    };
+
+    let mut connections = state.remote_connections.lock().unwrap();
+    match connections.iter_mut().find(|c| c.id == connection.id) {
+        Some(existing) => {
+            existing.failure_count = failure_count;
+            existing.needs_reauth()
+        }
+        None => failure_count >= REAUTH_FAILURE_THRESHOLD,
+    }
+}
+
+#[command]
+pub async fn get_remote_namespaces(refresh: Option<bool>, state: State<'_, AppState>) -> Result<RemoteNamespaces, ApiError> {
+    let connections = state.remote_connections.lock().unwrap().clone();
+    let refresh = refresh.unwrap_or(false);
+    let state = &*state;
+
+    let results = join_all(connections.into_iter().map(|mut connection| async move {
+        let cache_key = CacheKey::namespaces(&connection.account_id);
+        let result = cached_fetch(state, cache_key, refresh, async {
+            resolve_credentials(&mut connection).await?;
+            fetch_namespaces(&Client::new(), &connection).await
+        }).await;
+        (connection, result)
+    })).await;
+
+    let mut all_namespaces = Vec::new();
+    let mut errors = Vec::new();
+
+    for (connection, result) in results {
+        let (namespaces, synced_at) = match result {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                errors.push(AccountError {
+                    account_id: connection.account_id.clone(),
+                    label: connection.label.clone(),
+                    needs_reauth: note_connection_failure(state, &connection, &error),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        note_connection_success(state, &connection);
+        {
+            let db = state.db.lock().unwrap();
+            if let Err(e) = db.update_connection_timestamp(&connection.account_id) {
+                eprintln!("Failed to update connection timestamp: {}", e);
+            }
+        }
+
+        for namespace in namespaces {
+            all_namespaces.push(KVNamespace {
+                id: namespace.id.clone(),
+                name: namespace.title,
+                entries: vec![],
+                r#type: "remote".to_string(),
+                account_id: Some(connection.account_id.clone()),
+                folder_id: None,
+                count: None,
+                synced_at: Some(synced_at),
+            });
+        }
+    }
+
+    Ok(RemoteNamespaces {
+        namespaces: all_namespaces,
+        errors,
+    })
+}
+
+async fn fetch_key_page(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    cursor: Option<&str>,
+    prefix: Option<&str>
+) -> Result<CloudflareKeysResponse, ApiError> {
+    let mut url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/keys?limit=1000",
+        connection.account_id, namespace_id
+    );
+
+    if let Some(prefix) = prefix {
+        url = format!("{}&prefix={}", url, encode_key(prefix));
+    }
+
+    if let Some(cursor_value) = cursor {
+        url = format!("{}&cursor={}", url, cursor_value);
+    }
+
+    let response = client
+        .get(&url)
+        .with_auth(&connection.auth)
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let response = check_response(response).await?;
+
+    let response_data: CloudflareListResponse<CloudflareKey> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    if !response_data.success {
+        return Err(envelope_error(&response_data.errors));
+    }
+
+    let mut entries = Vec::new();
+    for (index, key) in response_data.result.iter().enumerate() {
+        entries.push(KVEntry {
+            id: format!("{}-{}", namespace_id, index),
+            key: key.name.clone(),
+            blob_id: format!("remote-{}", index),
+            expiration: key.expiration,
+            metadata: key.metadata.as_ref().map(|m| m.to_string()),
+            value: None,
+        });
+    }
+
+    let next_cursor = response_data.result_info.as_ref().and_then(|info| info.cursor.clone());
+    let total_count = response_data.result_info.as_ref().map(|info| info.count).unwrap_or(0);
+
+    Ok(CloudflareKeysResponse {
+        entries,
+        cursor: next_cursor,
+        total: total_count,
+        synced_at: None,
+        offline: false,
+    })
+}
+
+#[command]
+pub async fn get_remote_keys(
+    account_id: String,
+    namespace_id: String,
+    cursor: Option<String>,
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<CloudflareKeysResponse, ApiError> {
+    let mut connection = find_connection(&state, &account_id)?;
+
+    require_permission(&connection, KVPermission::Read)?;
+
+    let cache_key = CacheKey::keys(&account_id, &namespace_id, cursor.as_deref());
+    let (mut page, synced_at) = cached_fetch(&state, cache_key, refresh.unwrap_or(false), async {
+        resolve_credentials(&mut connection).await?;
+        fetch_key_page(&Client::new(), &connection, &namespace_id, cursor.as_deref(), None).await
+    }).await?;
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_connection_timestamp(&account_id) {
+            eprintln!("Failed to update connection timestamp: {}", e);
+        }
+    }
+
+    page.synced_at = Some(synced_at);
+    page.offline = is_offline(&state);
+
+    Ok(page)
+}
+
+async fn fetch_remote_value(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    key_name: &str
+) -> Result<KVValue, ApiError> {
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}",
+        connection.account_id, namespace_id, encode_key(key_name)
+    );
+
+    let response = client
+        .get(&url)
+        .with_auth(&connection.auth)
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let response = check_response(response).await?;
+
+    let bytes = response.bytes().await
+        .map_err(|e| format!("Failed to get response body: {}", e))?;
+
+    Ok(KVValue::from_bytes(bytes.to_vec()))
+}
+
+async fn fetch_remote_values_bulk(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    keys: &[String]
+) -> Result<Option<HashMap<String, Option<String>>>, ApiError> {
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/bulk/get",
+        connection.account_id, namespace_id
+    );
+
+    let response = client
+        .post(&url)
+        .with_auth(&connection.auth)
+        .json(&serde_json::json!({ "keys": keys, "type": "text" }))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND || status == StatusCode::METHOD_NOT_ALLOWED {
+        return Ok(None);
+    }
+    let response = check_response(response).await?;
+
+    let response_data: CloudflareResponse<CloudflareBulkGetResult> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+    if !response_data.success {
+        return Err(envelope_error(&response_data.errors));
+    }
+
+    Ok(response_data.result.map(|r| r.values))
+}
+
+async fn fetch_remote_values_individually(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    keys: Vec<String>
+) -> Vec<(String, Result<KVValue, ApiError>)> {
+    stream::iter(keys)
+        .map(|key_name| async move {
+            rate_limit::throttle_background(&connection.auth).await;
+            let value = fetch_remote_value(client, connection, namespace_id, &key_name).await;
+            (key_name, value)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
+}
+
+#[command]
+pub async fn get_remote_value(
+    account_id: String,
+    namespace_id: String,
+    key_name: String,
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<KVValue, ApiError> {
+    let mut connection = find_connection(&state, &account_id)?;
 
-    let status = response.status();
-    if !status.is_success() {
-        return Err(format!("API authentication failed with status: {}", status));
-    }
+    require_permission(&connection, KVPermission::Read)?;
 
-    {
-        let db = state.db.lock().unwrap();
-        if let Err(e) = db.save_remote_connection(&account_id, &api_token) {
-            return Err(format!("Failed to save connection: {}", e));
-        }
-    }
+    let cache_key = CacheKey::value(&account_id, &namespace_id, &key_name);
+    let (value, _) = cached_fetch(&state, cache_key, refresh.unwrap_or(false), async {
+        resolve_credentials(&mut connection).await?;
+        fetch_remote_value(&Client::new(), &connection, &namespace_id, &key_name).await
+    }).await?;
 
     {
-        let mut connections = state.remote_connections.lock().unwrap();
-        if !connections.iter().any(|c| c.account_id == account_id) {
-            connections.push(RemoteConnection {
-                account_id,
-                api_token,
-            });
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_connection_timestamp(&account_id) {
+            eprintln!("Failed to update connection timestamp: {}", e);
         }
     }
 
-    Ok(())
+    Ok(value)
 }
 
 #[command]
-pub async fn get_remote_namespaces(state: State<'_, AppState>) -> Result<Vec<KVNamespace>, String> {
-    let connections = state.remote_connections.lock().unwrap().clone();
-    if connections.is_empty() {
-        return Ok(vec![]);
+pub async fn get_remote_values(
+    account_id: String,
+    namespace_id: String,
+    keys: Vec<String>,
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<HashMap<String, BulkValueResult>, ApiError> {
+    let mut connection = find_connection(&state, &account_id)?;
+
+    require_permission(&connection, KVPermission::Read)?;
+
+    let mut results = HashMap::new();
+    let mut cached_values = HashMap::new();
+    let mut keys_to_fetch = Vec::new();
+
+    for key_name in keys {
+        match read_cached::<KVValue>(&state, &CacheKey::value(&account_id, &namespace_id, &key_name)) {
+            Some((value, _, true)) if !refresh.unwrap_or(false) => {
+                results.insert(key_name, BulkValueResult::value(value));
+            }
+            Some((value, _, _)) => {
+                cached_values.insert(key_name.clone(), value);
+                keys_to_fetch.push(key_name);
+            }
+            None => keys_to_fetch.push(key_name),
+        }
     }
 
-    let mut all_namespaces = Vec::new();
+    if keys_to_fetch.is_empty() {
+        return Ok(results);
+    }
 
-    for connection in connections {
-        let client = Client::new();
+    resolve_credentials(&mut connection).await?;
 
-        let namespaces_url = format!(
-            "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces",
-            connection.account_id
-        );
+    let client = Client::new();
+    let mut fetched = HashMap::new();
+    let mut remaining = Vec::new();
+    let mut bulk_available = true;
 
-        let namespaces_response = client
-            .get(&namespaces_url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-            .send()
-            .await
-            .map_err(|e| format!("API request failed: {}", e))?;
+    for chunk in keys_to_fetch.chunks(BULK_GET_LIMIT) {
+        rate_limit::throttle_background(&connection.auth).await;
 
-        if !namespaces_response.status().is_success() {
-            return Err(format!("API request failed with status: {}", namespaces_response.status()));
-        }
+        let values = if bulk_available {
+            match fetch_remote_values_bulk(&client, &connection, &namespace_id, chunk).await {
+                Ok(values) => values,
+                Err(e) => {
+                    for key_name in chunk {
+                        fetched.insert(key_name.clone(), Err(e.clone()));
+                    }
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
 
-        let namespaces_data: CloudflareListResponse<CloudflareNamespace> = namespaces_response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse API response: {}", e))?;
+        let values = match values {
+            Some(values) => values,
+            None => {
+                bulk_available = false;
+                remaining.extend_from_slice(chunk);
+                continue;
+            }
+        };
 
-        if !namespaces_data.success {
-            let error_msg = namespaces_data.errors
-                .iter()
-                .map(|e| format!("{}: {}", e.code, e.message))
-                .collect::<Vec<_>>()
-                .join(", ");
-            return Err(format!("API request failed: {}", error_msg));
+        for key_name in chunk {
+            match values.get(key_name) {
+                // Bulk get only returns text, so anything that did not survive UTF-8 decoding is fetched raw.
+                Some(Some(text)) if !text.contains('\u{FFFD}') => {
+                    fetched.insert(key_name.clone(), Ok(KVValue::from_bytes(text.clone().into_bytes())));
+                }
+                Some(None) => {
+                    fetched.insert(key_name.clone(), Err(ApiError::KeyNotFound { message: "Key not found".to_string() }));
+                }
+                _ => remaining.push(key_name.clone()),
+            }
         }
+    }
 
-        {
-            let db = state.db.lock().unwrap();
-            if let Err(e) = db.update_connection_timestamp(&connection.account_id) {
-                eprintln!("Failed to update connection timestamp: {}", e);
+    fetched.extend(fetch_remote_values_individually(&client, &connection, &namespace_id, remaining).await);
+
+    let mut network_failed = false;
+    for (key_name, value) in fetched {
+        let cache_key = CacheKey::value(&account_id, &namespace_id, &key_name);
+        let result = match value {
+            Ok(value) => {
+                store_cached(&state, &cache_key, &value);
+                BulkValueResult::value(value)
             }
-        }
+            Err(e) if e.is_network() => {
+                network_failed = true;
+                match cached_values.remove(&key_name) {
+                    Some(value) => BulkValueResult::value(value),
+                    None => BulkValueResult::error(e),
+                }
+            }
+            Err(e) => BulkValueResult::error(e),
+        };
+        results.insert(key_name, result);
+    }
 
-        for namespace in namespaces_data.result {
+    set_offline(&state, network_failed);
 
-            all_namespaces.push(KVNamespace {
-                id: namespace.id.clone(),
-                name: namespace.title,
-                entries: vec![],
-                r#type: "remote".to_string(),
-                account_id: Some(connection.account_id.clone()),
-                folder_id: None,
-                count: None,
-            });
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_connection_timestamp(&account_id) {
+            eprintln!("Failed to update connection timestamp: {}", e);
         }
     }
 
-    Ok(all_namespaces)
+    Ok(results)
+}
+
+async fn fetch_remote_snapshot(
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    key_name: &str
+) -> Result<Option<RemoteValueSnapshot>, ApiError> {
+    let value = match fetch_remote_value(client, connection, namespace_id, key_name).await {
+        Ok(value) => value,
+        Err(ApiError::KeyNotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let metadata = match fetch_remote_metadata(client, connection, namespace_id, key_name).await {
+        Ok(metadata) => metadata,
+        Err(ApiError::KeyNotFound { .. }) => None,
+        Err(e) => return Err(e),
+    };
+
+    Ok(Some(RemoteValueSnapshot::new(value, metadata)))
 }
 
 #[command]
-pub async fn get_remote_keys(
+pub async fn open_remote_value(
     account_id: String,
     namespace_id: String,
-    cursor: Option<String>,
+    key_name: String,
     state: State<'_, AppState>
-) -> Result<CloudflareKeysResponse, String> {
-    let connections = state.remote_connections.lock().unwrap().clone();
-    let connection = connections
-        .iter()
-        .find(|c| c.account_id == account_id)
-        .ok_or_else(|| "Connection not found".to_string())?;
+) -> Result<RemoteValueSnapshot, ApiError> {
+    let connection = connection_for_account(&state, &account_id).await?;
+
+    require_permission(&connection, KVPermission::Read)?;
 
     let client = Client::new();
+    let snapshot = fetch_remote_snapshot(&client, &connection, &namespace_id, &key_name)
+        .await?
+        .ok_or_else(|| ApiError::KeyNotFound { message: format!("{} no longer exists", key_name) })?;
 
-    let mut url = format!(
-        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/keys?limit=1000",
-        account_id, namespace_id
-    );
+    store_cached(&state, &CacheKey::value(&account_id, &namespace_id, &key_name), &snapshot.value);
+    store_cached(&state, &CacheKey::metadata(&account_id, &namespace_id, &key_name), &snapshot.metadata);
 
-    if let Some(cursor_value) = cursor {
-        url = format!("{}&cursor={}", url, cursor_value);
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.update_connection_timestamp(&account_id) {
+            eprintln!("Failed to update connection timestamp: {}", e);
+        }
     }
 
-    let response = client
-        .get(&url)
-        .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
+    Ok(snapshot)
+}
 
-    if !response.status().is_success() {
-        return Err(format!("API request failed with status: {}", response.status()));
-    }
+#[command]
+pub async fn get_remote_metadata(
+    account_id: String,
+    namespace_id: String,
+    key_name: String,
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<Option<Value>, ApiError> {
+    let mut connection = find_connection(&state, &account_id)?;
 
-    let response_data: CloudflareListResponse<CloudflareKey> = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse API response: {}", e))?;
+    require_permission(&connection, KVPermission::Read)?;
 
-    if !response_data.success {
-        let error_msg = response_data.errors
-            .iter()
-            .map(|e| format!("{}: {}", e.code, e.message))
-            .collect::<Vec<_>>()
-            .join(", ");
-        return Err(format!("API request failed: {}", error_msg));
-    }
+    let cache_key = CacheKey::metadata(&account_id, &namespace_id, &key_name);
+    let (metadata, _) = cached_fetch(&state, cache_key, refresh.unwrap_or(false), async {
+        resolve_credentials(&mut connection).await?;
+        fetch_remote_metadata(&Client::new(), &connection, &namespace_id, &key_name).await
+    }).await?;
 
     {
         let db = state.db.lock().unwrap();
@@ -165,53 +1193,69 @@ pub async fn get_remote_keys(
         }
     }
 
-    let mut entries = Vec::new();
-    for (index, key) in response_data.result.iter().enumerate() {
-        entries.push(KVEntry {
-            id: format!("{}-{}", namespace_id, index),
-            key: key.name.clone(),
-            blob_id: format!("remote-{}", index),
-            expiration: key.expiration,
-            metadata: key.metadata.as_ref().map(|m| m.to_string()),
-            value: None,
-        });
-    }
+    Ok(metadata)
+}
 
-    let next_cursor = response_data.result_info.as_ref().and_then(|info| info.cursor.clone());
-    let total_count = response_data.result_info.as_ref().map(|info| info.count).unwrap_or(0);
+async fn fetch_remote_metadata_individually(
+    state: &AppState,
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    keys: Vec<String>
+) -> Vec<(String, BulkMetadataResult)> {
+    stream::iter(keys)
+        .map(|key_name| async move {
+            let cache_key = CacheKey::metadata(&connection.account_id, namespace_id, &key_name);
+            let metadata = cached_fetch(state, cache_key, true, async {
+                rate_limit::throttle_background(&connection.auth).await;
+                fetch_remote_metadata(client, connection, namespace_id, &key_name).await
+            }).await;
 
-    Ok(CloudflareKeysResponse {
-        entries,
-        cursor: next_cursor,
-        total: total_count,
-    })
+            let result = match metadata {
+                Ok((metadata, _)) => BulkMetadataResult::metadata(metadata),
+                Err(ApiError::KeyNotFound { .. }) => BulkMetadataResult::metadata(None),
+                Err(e) => BulkMetadataResult::error(e),
+            };
+            (key_name, result)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
 }
 
 #[command]
-pub async fn get_remote_value(account_id: String, namespace_id: String, key_name: String, state: State<'_, AppState>) -> Result<Value, String> {
-    let connections = state.remote_connections.lock().unwrap().clone();
-    let connection = connections
-        .iter()
-        .find(|c| c.account_id == account_id)
-        .ok_or_else(|| "Connection not found".to_string())?;
+pub async fn get_remote_keys_metadata(
+    account_id: String,
+    namespace_id: String,
+    keys: Vec<String>,
+    refresh: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<HashMap<String, BulkMetadataResult>, ApiError> {
+    let mut connection = find_connection(&state, &account_id)?;
 
-    let client = Client::new();
-    let url = format!(
-        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}",
-        account_id, namespace_id, key_name
-    );
+    require_permission(&connection, KVPermission::Read)?;
 
-    let response = client
-        .get(&url)
-        .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-        .send()
-        .await
-        .map_err(|e| format!("API request failed: {}", e))?;
+    let refresh = refresh.unwrap_or(false);
+    let mut results = HashMap::new();
+    let mut keys_to_fetch = Vec::new();
 
-    if !response.status().is_success() {
-        return Err(format!("API request failed with status: {}", response.status()));
+    for key_name in keys {
+        match read_cached::<Option<Value>>(&state, &CacheKey::metadata(&account_id, &namespace_id, &key_name)) {
+            Some((metadata, _, true)) if !refresh => {
+                results.insert(key_name, BulkMetadataResult::metadata(metadata));
+            }
+            _ => keys_to_fetch.push(key_name),
+        }
+    }
+
+    if keys_to_fetch.is_empty() {
+        return Ok(results);
     }
 
+    resolve_credentials(&mut connection).await?;
+
+    results.extend(fetch_remote_metadata_individually(&state, &Client::new(), &connection, &namespace_id, keys_to_fetch).await);
+
     {
         let db = state.db.lock().unwrap();
         if let Err(e) = db.update_connection_timestamp(&account_id) {
@@ -219,44 +1263,68 @@ pub async fn get_remote_value(account_id: String, namespace_id: String, key_name
         }
     }
 
-    let text = response.text().await
-        .map_err(|e| format!("Failed to get response text: {}", e))?;
-
-    match serde_json::from_str::<Value>(&text) {
-        Ok(value) => Ok(value),
-        Err(_) => Ok(Value::String(text)),
-    }
+    Ok(results)
 }
 
 #[command]
-pub async fn update_remote_kv(account_id: String, namespace_id: String, key_name: String, value: String, state: State<'_, AppState>) -> Result<(), String> {
-    let connections = state.remote_connections.lock().unwrap().clone();
-    let connection = connections
-        .iter()
-        .find(|c| c.account_id == account_id)
-        .ok_or_else(|| "Connection not found".to_string())?;
+pub async fn update_remote_kv(
+    account_id: String,
+    namespace_id: String,
+    key_name: String,
+    value: KVValue,
+    expected_fingerprint: Option<String>,
+    force: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<(), ApiError> {
+    require_online(&state)?;
+
+    let connection = connection_for_account(&state, &account_id).await?;
+
+    require_permission(&connection, KVPermission::Write)?;
 
     let client = Client::new();
     let url = format!(
         "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}",
-        account_id, namespace_id, key_name
+        account_id, namespace_id, encode_key(&key_name)
     );
 
-    let _: Value = serde_json::from_str(&value)
-        .map_err(|_| "Invalid JSON value".to_string())?;
+    if value.kind == ValueKind::Json && serde_json::from_slice::<Value>(&value.bytes).is_err() {
+        return Err("Invalid JSON value".into());
+    }
+
+    if let (Some(expected), false) = (&expected_fingerprint, force.unwrap_or(false)) {
+        let current = fetch_remote_snapshot(&client, &connection, &namespace_id, &key_name).await?;
+        let current_fingerprint = match &current {
+            Some(snapshot) => snapshot.fingerprint.clone(),
+            None => fingerprint(None, None),
+        };
+
+        if &current_fingerprint != expected {
+            return Err(ApiError::Conflict {
+                message: format!(
+                    "{} was changed remotely after it was opened. Review both versions or force the overwrite.",
+                    key_name
+                ),
+                remote: current.map(Box::new),
+                local: Box::new(value),
+            });
+        }
+    }
 
     let response = client
         .put(&url)
-        .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-        .header(header::CONTENT_TYPE, "application/json")
-        .body(value)
+        .with_auth(&connection.auth)
+        .header(header::CONTENT_TYPE, value.content_type())
+        .body(value.bytes.clone())
         .send()
         .await
-        .map_err(|e| format!("API request failed: {}", e))?;
+        .map_err(request_error)?;
 
-    if !response.status().is_success() {
-        return Err(format!("API request failed with status: {}", response.status()));
-    }
+    check_response(response).await?;
+
+    store_cached(&state, &CacheKey::value(&account_id, &namespace_id, &key_name), &value);
+    forget_cached(&state, &CacheKey::metadata(&account_id, &namespace_id, &key_name));
+    invalidate_cached_keys(&state, &account_id, &namespace_id);
 
     {
         let db = state.db.lock().unwrap();
@@ -268,15 +1336,78 @@ pub async fn update_remote_kv(account_id: String, namespace_id: String, key_name
     Ok(())
 }
 
+pub async fn delete_remote_keys(
+    state: &AppState,
+    job: &JobHandle,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    keys: &[String]
+) -> Result<usize, ApiError> {
+    let client = Client::new();
+    let mut deleted = 0;
+
+    for chunk in keys.chunks(BULK_DELETE_LIMIT) {
+        job.ensure_active::<ApiError>()?;
+        rate_limit::throttle_background(&connection.auth).await;
+
+        if chunk.len() == 1 {
+            let url = format!(
+                "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}",
+                connection.account_id, namespace_id, encode_key(&chunk[0])
+            );
+
+            let response = client
+                .delete(&url)
+                .with_auth(&connection.auth)
+                .send()
+                .await
+                .map_err(request_error)?;
+
+            check_response(response).await?;
+        } else {
+            let url = format!(
+                "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/bulk/delete",
+                connection.account_id, namespace_id
+            );
+
+            let response = client
+                .post(&url)
+                .with_auth(&connection.auth)
+                .header(header::CONTENT_TYPE, "application/json")
+                .json(&chunk)
+                .send()
+                .await
+                .map_err(request_error)?;
+
+            check_response(response).await?;
+        }
+
+        for key_name in chunk {
+            forget_cached(state, &CacheKey::value(&connection.account_id, namespace_id, key_name));
+            forget_cached(state, &CacheKey::metadata(&connection.account_id, namespace_id, key_name));
+        }
+        invalidate_cached_keys(state, &connection.account_id, namespace_id);
+
+        deleted += chunk.len();
+        job.progress(deleted as u64, Some(keys.len() as u64), None);
+    }
+
+    Ok(deleted)
+}
+
 #[command]
-pub async fn delete_remote_kv(account_id: String, namespace_id: String, keys: Vec<String>, state: State<'_, AppState>) -> Result<(), String> {
-    let connections = state.remote_connections.lock().unwrap().clone();
-    let connection = connections
-        .iter()
-        .find(|c| c.account_id == account_id)
-        .ok_or_else(|| "Connection not found".to_string())?;
+pub async fn delete_remote_kv(
+    app: AppHandle,
+    account_id: String,
+    namespace_id: String,
+    keys: Vec<String>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    require_online(&state)?;
 
-    let client = Client::new();
+    let connection = connection_for_account(&state, &account_id).await?;
+
+    require_permission(&connection, KVPermission::Write)?;
 
     {
         let db = state.db.lock().unwrap();
@@ -285,51 +1416,237 @@ pub async fn delete_remote_kv(account_id: String, namespace_id: String, keys: Ve
         }
     }
 
-    if keys.len() == 1 {
-        let url = format!(
-            "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/values/{}",
-            account_id, namespace_id, keys[0]
-        );
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "delete_remote_kv", move |job| async move {
+        let state = task_app.state::<AppState>();
+        delete_remote_keys(&state, &job, &connection, &namespace_id, &keys).await
+    });
 
-        let response = client
-            .delete(&url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-            .send()
-            .await
-            .map_err(|e| format!("API request failed: {}", e))?;
+    Ok(job_id)
+}
+
+pub async fn fetch_remote_key_page(
+    state: &AppState,
+    client: &Client,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    cursor: Option<&str>,
+    prefix: Option<&str>
+) -> Result<CloudflareKeysResponse, ApiError> {
+    rate_limit::throttle_background(&connection.auth).await;
 
-        if !response.status().is_success() {
-            return Err(format!("API request failed with status: {}", response.status()));
+    let page = fetch_key_page(client, connection, namespace_id, cursor, prefix).await?;
+    if prefix.is_none() {
+        store_cached(state, &CacheKey::keys(&connection.account_id, namespace_id, cursor), &page);
+    }
+
+    Ok(page)
+}
+
+pub async fn list_all_remote_keys(
+    state: &AppState,
+    job: &JobHandle,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    prefix: Option<&str>
+) -> Result<Vec<KVEntry>, ApiError> {
+    let client = Client::new();
+    let mut entries = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        job.ensure_active::<ApiError>()?;
+
+        let page = fetch_remote_key_page(state, &client, connection, namespace_id, cursor.as_deref(), prefix).await?;
+
+        for mut entry in page.entries {
+            entry.id = format!("{}-{}", namespace_id, entries.len());
+            entries.push(entry);
         }
-    } else {
-        let url = format!(
-            "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/bulk/delete",
-            account_id, namespace_id
-        );
+        job.progress(entries.len() as u64, None, Some("Listing remote keys".to_string()));
+
+        match page.cursor.filter(|c| !c.is_empty()) {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Writes records with the bulk endpoint, splitting batches at the pair limit
+/// and well below the 100 MB request body limit.
+pub async fn write_remote_records(
+    state: &AppState,
+    job: &JobHandle,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    records: &[KVRecord]
+) -> Result<usize, ApiError> {
+    let client = Client::new();
+    let url = format!(
+        "https://api.cloudflare.com/client/v4/accounts/{}/storage/kv/namespaces/{}/bulk",
+        connection.account_id, namespace_id
+    );
+    let mut written = 0;
+    let mut remaining = records;
+
+    while !remaining.is_empty() {
+        job.ensure_active::<ApiError>()?;
+        rate_limit::throttle_background(&connection.auth).await;
+
+        let mut batch = Vec::new();
+        let mut batch_bytes = 0;
+        for record in remaining.iter().take(BULK_WRITE_LIMIT) {
+            let entry = CloudflareBulkWrite::from(record);
+            let entry_bytes = entry.key.len() + entry.value.len();
+            if !batch.is_empty() && batch_bytes + entry_bytes > BULK_WRITE_MAX_BYTES {
+                break;
+            }
+            batch_bytes += entry_bytes;
+            batch.push(entry);
+        }
+        let (chunk, rest) = remaining.split_at(batch.len());
+        remaining = rest;
 
         let response = client
-            .post(&url)
-            .header(header::AUTHORIZATION, format!("Bearer {}", connection.api_token))
-            .header(header::CONTENT_TYPE, "application/json")
-            .json(&keys)
+            .put(&url)
+            .with_auth(&connection.auth)
+            .json(&batch)
             .send()
             .await
-            .map_err(|e| format!("API request failed: {}", e))?;
+            .map_err(request_error)?;
+
+        let response = check_response(response).await?;
+
+        let response_data: CloudflareResponse<CloudflareBulkWriteResult> = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse API response: {}", e))?;
+
+        if !response_data.success {
+            return Err(envelope_error(&response_data.errors));
+        }
+
+        for record in chunk {
+            forget_cached(state, &CacheKey::value(&connection.account_id, namespace_id, &record.key));
+            forget_cached(state, &CacheKey::metadata(&connection.account_id, namespace_id, &record.key));
+        }
+        invalidate_cached_keys(state, &connection.account_id, namespace_id);
 
-        if !response.status().is_success() {
-            return Err(format!("API request failed with status: {}", response.status()));
+        if let Some(failed) = response_data.result.map(|r| r.unsuccessful_keys).filter(|keys| !keys.is_empty()) {
+            return Err(format!(
+                "{} keys were written, but Cloudflare rejected {}: {}",
+                written + chunk.len() - failed.len(),
+                failed.len(),
+                failed.join(", ")
+            ).into());
         }
+
+        written += chunk.len();
+        job.progress(written as u64, Some(records.len() as u64), Some("Writing remote keys".to_string()));
     }
 
-    Ok(())
+    Ok(written)
+}
+
+/// One request per key, since the bulk endpoint only returns text.
+pub async fn fetch_remote_records(
+    job: &JobHandle,
+    connection: &RemoteConnection,
+    namespace_id: &str,
+    keys: Vec<KeyInfo>
+) -> Result<Vec<KVRecord>, ApiError> {
+    let client = Client::new();
+    let total = keys.len() as u64;
+    let mut fetched = stream::iter(keys)
+        .map(|entry| {
+            let client = &client;
+            async move {
+                job.ensure_active::<ApiError>()?;
+                rate_limit::throttle_background(&connection.auth).await;
+                let value = fetch_remote_value(client, connection, namespace_id, &entry.key).await;
+                Ok::<_, ApiError>((entry, value))
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS);
+
+    let mut records = Vec::new();
+    let mut failed = Vec::new();
+    let mut completed = 0;
+    while let Some(result) = fetched.next().await {
+        let (entry, value) = result?;
+        completed += 1;
+        job.progress(completed, Some(total), Some("Downloading values".to_string()));
+
+        let value = match value {
+            Ok(value) => value,
+            Err(ApiError::KeyNotFound { .. }) => continue,
+            Err(e) => {
+                failed.push((entry.key, e));
+                continue;
+            }
+        };
+
+        records.push(KVRecord {
+            key: entry.key,
+            value,
+            expiration: entry.expiration,
+            metadata: entry.metadata,
+            modified_at: None,
+        });
+    }
+
+    // A missing record would read as a deleted key to callers, so the batch
+    // only fails once every key has been tried and the failures can be named.
+    if !failed.is_empty() {
+        failed.sort_by(|a, b| a.0.cmp(&b.0));
+        let keys: Vec<&str> = failed.iter().take(10).map(|(key, _)| key.as_str()).collect();
+        return Err(format!("Failed to download {} of {} keys ({}): {}", failed.len(), total, keys.join(", "), failed[0].1).into());
+    }
+
+    records.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(records)
+}
+
+#[command]
+pub async fn list_remote_keys(
+    app: AppHandle,
+    account_id: String,
+    namespace_id: String,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let connection = connection_for_account(&state, &account_id).await?;
+
+    require_permission(&connection, KVPermission::Read)?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "list_remote_keys", move |job| async move {
+        let state = task_app.state::<AppState>();
+        let entries = list_all_remote_keys(&state, &job, &connection, &namespace_id, None).await?;
+
+        {
+            let db = state.db.lock().unwrap();
+            if let Err(e) = db.update_connection_timestamp(&account_id) {
+                eprintln!("Failed to update connection timestamp: {}", e);
+            }
+        }
+
+        Ok::<_, ApiError>(entries)
+    });
+
+    Ok(job_id)
 }
 
 #[command]
-pub async fn disconnect_cloudflare(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn disconnect_cloudflare(state: State<'_, AppState>) -> Result<(), ApiError> {
     {
         let db = state.db.lock().unwrap();
         if let Err(e) = db.remove_all_connections() {
-            return Err(format!("Failed to remove connections from database: {}", e));
+            return Err(format!("Failed to remove connections from database: {}", e).into());
+        }
+        if let Err(e) = db.clear_cache(None) {
+            eprintln!("Failed to clear cache: {}", e);
         }
     }
 
@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::local::{entries_modified_at, kv_storage_path, local_namespace_names, read_local_blob, read_local_revisions};
+use crate::jobs::JobHandle;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::search::{SearchDocument, SearchHit, SearchOptions, SearchResults};
+use crate::models::transfer::NamespaceRef;
+use crate::models::value::KVValue;
+use crate::search::{indexable_text, match_query, split_snippet};
+
+const INDEX_BATCH_SIZE: usize = 200;
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+
+const CACHE_MARK: &str = "remote_cache";
+
+type NamespaceKey = (String, String);
+
+fn plan_update<'a>(
+    indexed: &HashMap<String, String>,
+    current: &'a [(String, String)],
+    live: &HashSet<&str>
+) -> (Vec<&'a (String, String)>, Vec<String>) {
+    let changed = current.iter().filter(|(key, revision)| indexed.get(key) != Some(revision)).collect();
+    let mut removed: Vec<String> = indexed.keys().filter(|key| !live.contains(key.as_str())).cloned().collect();
+    removed.sort();
+
+    (changed, removed)
+}
+
+fn update_source(
+    state: &AppState,
+    job: &JobHandle,
+    source: &NamespaceRef,
+    current: &[(String, String)],
+    live: &HashSet<&str>,
+    load: impl Fn(&str, &str) -> Option<KVValue>
+) -> Result<(usize, bool), String> {
+    let indexed = {
+        let db = state.db.lock().unwrap();
+        db.get_search_revisions(source).map_err(|e| format!("Failed to read search index: {}", e))?
+    };
+    let (changed, removed) = plan_update(&indexed, current, live);
+    let mut indexed = 0;
+
+    for chunk in changed.chunks(INDEX_BATCH_SIZE) {
+        job.ensure_active::<String>()?;
+
+        let documents: Vec<SearchDocument> = chunk
+            .iter()
+            .filter_map(|(key, revision)| {
+                let value = load(key, revision)?;
+                Some(SearchDocument { key: key.clone(), revision: revision.clone(), content: indexable_text(&value) })
+            })
+            .collect();
+        indexed += documents.len();
+
+        let db = state.db.lock().unwrap();
+        db.index_search_documents(source, &documents).map_err(|e| format!("Failed to update search index: {}", e))?;
+    }
+
+    if !removed.is_empty() {
+        let db = state.db.lock().unwrap();
+        db.remove_search_documents(source, &removed).map_err(|e| format!("Failed to update search index: {}", e))?;
+    }
+
+    Ok((indexed, indexed == changed.len()))
+}
+
+fn read_mark(state: &AppState, name: &str) -> Result<Option<i64>, String> {
+    let db = state.db.lock().unwrap();
+    db.get_search_mark(name).map_err(|e| format!("Failed to read search index: {}", e))
+}
+
+fn write_mark(state: &AppState, name: &str, indexed_through: i64) -> Result<(), String> {
+    let db = state.db.lock().unwrap();
+    db.set_search_mark(name, indexed_through).map_err(|e| format!("Failed to update search index: {}", e))
+}
+
+/// Skips local namespaces whose entries are unchanged and cache rows older than the last refresh.
+/// A mark only advances once every changed value was read, so failed reads are retried.
+fn refresh_index(state: &AppState, job: &JobHandle) -> Result<usize, String> {
+    let folders: Vec<_> = state.folders.lock().unwrap().values().map(|f| (f.id, f.path.clone())).collect();
+    let mut live = Vec::new();
+    let mut reindexed = 0;
+
+    for (folder_id, path) in folders {
+        let kv_path = kv_storage_path(&path);
+        let Ok(names) = local_namespace_names(&kv_path) else {
+            continue;
+        };
+
+        for name in names {
+            let source = NamespaceRef::Local {
+                folder_id,
+                namespace_id: format!("folder-{}-ns-{}", folder_id, name),
+            };
+            let mark = source.descriptor();
+            let modified = entries_modified_at(&kv_path, &name);
+            if modified.is_some() && read_mark(state, &mark)? == modified {
+                live.push(source);
+                continue;
+            }
+
+            let Ok(revisions) = read_local_revisions(&kv_path, &name) else {
+                continue;
+            };
+            let keys: HashSet<&str> = revisions.iter().map(|(key, _)| key.as_str()).collect();
+
+            let (indexed, complete) = update_source(state, job, &source, &revisions, &keys, |_, blob_id| {
+                read_local_blob(&kv_path, &name, blob_id).map(KVValue::from_bytes)
+            })?;
+            reindexed += indexed;
+            if let Some(modified) = modified.filter(|_| complete) {
+                write_mark(state, &mark, modified)?;
+            }
+            live.push(source);
+            job.progress(reindexed as u64, None, Some("Indexing local values".to_string()));
+        }
+    }
+
+    let synced_since = read_mark(state, CACHE_MARK)?.unwrap_or(0);
+    let (cached_keys, cached) = {
+        let db = state.db.lock().unwrap();
+        let keys = db.get_cached_value_keys().map_err(|e| format!("Failed to read cached values: {}", e))?;
+        let values = db.get_cached_values(synced_since).map_err(|e| format!("Failed to read cached values: {}", e))?;
+        (keys, values)
+    };
+
+    let mut namespaces: BTreeMap<NamespaceKey, HashSet<String>> = BTreeMap::new();
+    for (account_id, namespace_id, key) in cached_keys {
+        namespaces.entry((account_id, namespace_id)).or_default().insert(key);
+    }
+    let mut latest = synced_since;
+    let mut cache_complete = true;
+    let mut updated: HashMap<NamespaceKey, HashMap<String, (String, String)>> = HashMap::new();
+    for (account_id, namespace_id, key, synced_at, payload) in cached {
+        latest = latest.max(synced_at);
+        updated.entry((account_id, namespace_id)).or_default().insert(key, (synced_at.to_string(), payload));
+    }
+
+    for ((account_id, namespace_id), keys) in namespaces {
+        let values = updated.remove(&(account_id.clone(), namespace_id.clone())).unwrap_or_default();
+        let source = NamespaceRef::Remote { account_id, namespace_id };
+        let revisions: Vec<(String, String)> = values.iter().map(|(key, (revision, _))| (key.clone(), revision.clone())).collect();
+        let keys: HashSet<&str> = keys.iter().map(String::as_str).collect();
+
+        let (indexed, complete) = update_source(state, job, &source, &revisions, &keys, |key, _| {
+            values.get(key).and_then(|(_, payload)| serde_json::from_str(payload).ok())
+        })?;
+        reindexed += indexed;
+        cache_complete &= complete;
+        live.push(source);
+        job.progress(reindexed as u64, None, Some("Indexing cached values".to_string()));
+    }
+    if cache_complete {
+        write_mark(state, CACHE_MARK, latest)?;
+    }
+
+    let db = state.db.lock().unwrap();
+    let live: HashSet<String> = live.iter().map(NamespaceRef::descriptor).collect();
+    for source in db.list_search_sources().map_err(|e| format!("Failed to read search index: {}", e))? {
+        if !live.contains(&source.descriptor()) {
+            db.remove_search_source(&source).map_err(|e| format!("Failed to update search index: {}", e))?;
+        }
+    }
+
+    Ok(reindexed)
+}
+
+#[command]
+pub async fn search_values(
+    app: AppHandle,
+    query: String,
+    options: Option<SearchOptions>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let query = match_query(&query).ok_or_else(|| "Enter something to search for".to_string())?;
+    let options = options.unwrap_or_default();
+    let limit = options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "search_values", move |job| async move {
+        let reindexed = if options.refresh.unwrap_or(true) {
+            let (refresh_app, refresh_job) = (task_app.clone(), job.clone());
+            tauri::async_runtime::spawn_blocking(move || refresh_index(&refresh_app.state::<AppState>(), &refresh_job))
+                .await
+                .map_err(|e| format!("Failed to refresh search index: {}", e))??
+        } else {
+            0
+        };
+
+        let state = task_app.state::<AppState>();
+        let db = state.db.lock().unwrap();
+        let hits = db
+            .search_documents(&query, limit)
+            .map_err(|e| format!("Search failed: {}", e))?
+            .into_iter()
+            .map(|(namespace, key, snippet)| SearchHit { namespace, key, snippet: split_snippet(&snippet) })
+            .collect();
+
+        Ok::<_, ApiError>(SearchResults { hits, reindexed })
+    });
+
+    Ok(job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn only_changed_keys_are_reindexed() {
+        let indexed: HashMap<String, String> = pairs(&[("a", "1"), ("b", "1"), ("gone", "1")]).into_iter().collect();
+        let current = pairs(&[("a", "1"), ("b", "2"), ("new", "1")]);
+        let live: HashSet<&str> = ["a", "b", "new"].into_iter().collect();
+
+        let (changed, removed) = plan_update(&indexed, &current, &live);
+        let changed: Vec<&str> = changed.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(changed, vec!["b", "new"]);
+        assert_eq!(removed, vec!["gone".to_string()]);
+    }
+
+    #[test]
+    fn live_keys_outside_the_update_are_kept() {
+        let indexed: HashMap<String, String> = pairs(&[("old", "1")]).into_iter().collect();
+        let current = pairs(&[("fresh", "5")]);
+        let live: HashSet<&str> = ["old", "fresh"].into_iter().collect();
+
+        let (changed, removed) = plan_update(&indexed, &current, &live);
+        assert_eq!(changed.len(), 1);
+        assert!(removed.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+use std::collections::{BTreeSet, HashMap};
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::transfer::Endpoint;
+use crate::jobs::JobHandle;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::models::kv::KVPermission;
+use crate::models::sync::{SyncAction, SyncChange, SyncPlan, SyncPolicy, SyncResult};
+use crate::models::transfer::{KVRecord, NamespaceRef};
+
+fn pair_id(left: &NamespaceRef, right: &NamespaceRef) -> String {
+    format!("{}|{}", left.descriptor(), right.descriptor())
+}
+
+fn resolve_conflict(policy: SyncPolicy, left: Option<&KVRecord>, right: Option<&KVRecord>) -> SyncAction {
+    let keep_left = if left.is_some() { SyncAction::CopyToRight } else { SyncAction::DeleteRight };
+    let keep_right = if right.is_some() { SyncAction::CopyToLeft } else { SyncAction::DeleteLeft };
+
+    match policy {
+        SyncPolicy::PreferLeft => keep_left,
+        SyncPolicy::PreferRight => keep_right,
+        SyncPolicy::NewestWins => match (left.and_then(|r| r.modified_at), right.and_then(|r| r.modified_at)) {
+            (Some(l), Some(r)) if l > r => keep_left,
+            (Some(l), Some(r)) if r > l => keep_right,
+            _ => SyncAction::Conflict,
+        },
+        SyncPolicy::Ask => SyncAction::Conflict,
+    }
+}
+
+async fn load_records(state: &AppState, job: &JobHandle, endpoint: &mut Endpoint) -> Result<HashMap<String, KVRecord>, ApiError> {
+    let keys = endpoint.list(state, job).await?;
+    let now = chrono::Utc::now().timestamp();
+    Ok(endpoint
+        .fetch(job, keys)
+        .await?
+        .into_iter()
+        .filter(|r| !r.is_expired(now))
+        .map(|r| (r.key.clone(), r))
+        .collect())
+}
+
+fn read_baseline(state: &AppState, pair_id: &str) -> Result<HashMap<String, String>, ApiError> {
+    let db = state.db.lock().unwrap();
+    db.get_sync_baseline(pair_id).map_err(|e| format!("Failed to read sync baseline: {}", e).into())
+}
+
+fn update_baseline(state: &AppState, pair_id: &str, synced: &[(String, String)], removed: &[String]) -> Result<(), ApiError> {
+    let db = state.db.lock().unwrap();
+    db.save_sync_baseline(pair_id, synced)
+        .and_then(|_| db.remove_sync_baseline(pair_id, removed))
+        .map_err(|e| format!("Failed to update sync baseline: {}", e).into())
+}
+
+#[derive(Debug, Default)]
+struct Comparison {
+    changes: Vec<SyncChange>,
+    in_sync: Vec<(String, String)>,
+    removed: Vec<String>,
+}
+
+/// Compares both sides against the baseline from the last sync. A key that
+/// changed on one side only is copied over; a key that changed on both sides
+/// is a conflict settled by the policy.
+fn compare(
+    left_records: &HashMap<String, KVRecord>,
+    right_records: &HashMap<String, KVRecord>,
+    baseline: &HashMap<String, String>,
+    policy: SyncPolicy
+) -> Comparison {
+    let keys: BTreeSet<&String> = left_records.keys().chain(right_records.keys()).chain(baseline.keys()).collect();
+    let mut comparison = Comparison::default();
+
+    for key in keys {
+        let (left_record, right_record) = (left_records.get(key), right_records.get(key));
+        let left_fingerprint = left_record.map(KVRecord::fingerprint);
+        let right_fingerprint = right_record.map(KVRecord::fingerprint);
+
+        if left_fingerprint == right_fingerprint {
+            match left_fingerprint {
+                Some(fingerprint) => comparison.in_sync.push((key.clone(), fingerprint)),
+                None => comparison.removed.push(key.clone()),
+            }
+            continue;
+        }
+
+        let base = baseline.get(key);
+        let (action, conflict) = if base == left_fingerprint.as_ref() {
+            (if right_record.is_some() { SyncAction::CopyToLeft } else { SyncAction::DeleteLeft }, false)
+        } else if base == right_fingerprint.as_ref() {
+            (if left_record.is_some() { SyncAction::CopyToRight } else { SyncAction::DeleteRight }, false)
+        } else {
+            (resolve_conflict(policy, left_record, right_record), true)
+        };
+
+        comparison.changes.push(SyncChange {
+            key: key.clone(),
+            action,
+            conflict,
+            left_fingerprint,
+            right_fingerprint,
+        });
+    }
+
+    comparison
+}
+
+async fn build_plan(
+    state: &AppState,
+    job: &JobHandle,
+    (left_ref, mut left): (NamespaceRef, Endpoint),
+    (right_ref, mut right): (NamespaceRef, Endpoint),
+    policy: SyncPolicy
+) -> Result<SyncPlan, ApiError> {
+    let pair_id = pair_id(&left_ref, &right_ref);
+    let left_records = load_records(state, job, &mut left).await?;
+    let right_records = load_records(state, job, &mut right).await?;
+    let baseline = read_baseline(state, &pair_id)?;
+    let comparison = compare(&left_records, &right_records, &baseline, policy);
+
+    Ok(SyncPlan {
+        left: left_ref,
+        right: right_ref,
+        policy,
+        changes: comparison.changes,
+        in_sync: comparison.in_sync.len(),
+        has_baseline: !baseline.is_empty(),
+    })
+}
+
+async fn apply_plan(
+    state: &AppState,
+    job: &JobHandle,
+    plan: SyncPlan,
+    resolutions: HashMap<String, SyncAction>,
+    mut left: Endpoint,
+    mut right: Endpoint
+) -> Result<SyncResult, ApiError> {
+    let pair_id = pair_id(&plan.left, &plan.right);
+    let left_records = load_records(state, job, &mut left).await?;
+    let right_records = load_records(state, job, &mut right).await?;
+    // Keys that already match are only recorded once the writes below succeed.
+    let Comparison { in_sync: mut synced, mut removed, .. } =
+        compare(&left_records, &right_records, &read_baseline(state, &pair_id)?, plan.policy);
+
+    let mut result = SyncResult::default();
+    let (mut to_left, mut to_right) = (Vec::new(), Vec::new());
+    let (mut delete_left, mut delete_right) = (Vec::new(), Vec::new());
+
+    for change in plan.changes {
+        let (left_record, right_record) = (left_records.get(&change.key), right_records.get(&change.key));
+
+        if left_record.map(KVRecord::fingerprint) != change.left_fingerprint
+            || right_record.map(KVRecord::fingerprint) != change.right_fingerprint
+        {
+            result.stale.push(change.key);
+            continue;
+        }
+
+        match resolutions.get(&change.key).copied().unwrap_or(change.action) {
+            SyncAction::CopyToRight => match left_record {
+                Some(record) => {
+                    synced.push((change.key.clone(), record.fingerprint()));
+                    to_right.push(record.clone());
+                }
+                None => {
+                    removed.push(change.key.clone());
+                    delete_right.push(change.key);
+                }
+            },
+            SyncAction::CopyToLeft => match right_record {
+                Some(record) => {
+                    synced.push((change.key.clone(), record.fingerprint()));
+                    to_left.push(record.clone());
+                }
+                None => {
+                    removed.push(change.key.clone());
+                    delete_left.push(change.key);
+                }
+            },
+            SyncAction::DeleteRight => {
+                removed.push(change.key.clone());
+                delete_right.push(change.key);
+            }
+            SyncAction::DeleteLeft => {
+                removed.push(change.key.clone());
+                delete_left.push(change.key);
+            }
+            SyncAction::Conflict => result.unresolved.push(change.key),
+        }
+    }
+
+    if !to_right.is_empty() {
+        result.copied_to_right = right.write(state, job, to_right).await?;
+    }
+    if !to_left.is_empty() {
+        result.copied_to_left = left.write(state, job, to_left).await?;
+    }
+    if !delete_right.is_empty() {
+        result.deleted_right = right.delete(state, job, delete_right).await?;
+    }
+    if !delete_left.is_empty() {
+        result.deleted_left = left.delete(state, job, delete_left).await?;
+    }
+
+    update_baseline(state, &pair_id, &synced, &removed)?;
+
+    Ok(result)
+}
+
+#[command]
+pub async fn plan_sync(
+    app: AppHandle,
+    left: NamespaceRef,
+    right: NamespaceRef,
+    policy: SyncPolicy,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let left_endpoint = Endpoint::resolve(&state, &left, KVPermission::Read).await?;
+    let right_endpoint = Endpoint::resolve(&state, &right, KVPermission::Read).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "plan_sync", move |job| async move {
+        let state = task_app.state::<AppState>();
+        build_plan(&state, &job, (left, left_endpoint), (right, right_endpoint), policy).await
+    });
+
+    Ok(job_id)
+}
+
+/// Applies a plan from `plan_sync`. `resolutions` overrides the action for
+/// individual keys, which is how conflicts left open by the policy are settled.
+#[command]
+pub async fn apply_sync(
+    app: AppHandle,
+    plan: SyncPlan,
+    resolutions: Option<HashMap<String, SyncAction>>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let resolutions = resolutions.unwrap_or_default();
+    let writes = |targets: [SyncAction; 2]| {
+        plan.changes
+            .iter()
+            .any(|c| targets.contains(resolutions.get(&c.key).unwrap_or(&c.action)))
+    };
+    let permission = |write: bool| if write { KVPermission::Write } else { KVPermission::Read };
+
+    let left = Endpoint::resolve(
+        &state,
+        &plan.left,
+        permission(writes([SyncAction::CopyToLeft, SyncAction::DeleteLeft]))
+    ).await?;
+    let right = Endpoint::resolve(
+        &state,
+        &plan.right,
+        permission(writes([SyncAction::CopyToRight, SyncAction::DeleteRight]))
+    ).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "apply_sync", move |job| async move {
+        let state = task_app.state::<AppState>();
+        apply_plan(&state, &job, plan, resolutions, left, right).await
+    });
+
+    Ok(job_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::value::KVValue;
+
+    fn record(key: &str, value: &str, modified_at: Option<i64>) -> KVRecord {
+        KVRecord {
+            key: key.to_string(),
+            value: KVValue::from_bytes(value.as_bytes().to_vec()),
+            expiration: None,
+            metadata: None,
+            modified_at,
+        }
+    }
+
+    fn records(entries: &[&KVRecord]) -> HashMap<String, KVRecord> {
+        entries.iter().map(|r| (r.key.clone(), (*r).clone())).collect()
+    }
+
+    fn baseline(entries: &[&KVRecord]) -> HashMap<String, String> {
+        entries.iter().map(|r| (r.key.clone(), r.fingerprint())).collect()
+    }
+
+    fn actions(comparison: &Comparison) -> Vec<(&str, SyncAction, bool)> {
+        comparison.changes.iter().map(|c| (c.key.as_str(), c.action, c.conflict)).collect()
+    }
+
+    #[test]
+    fn one_sided_changes_are_copied() {
+        let old = record("a", "1", None);
+        let left = records(&[&record("a", "2", None), &record("new", "x", None)]);
+        let right = records(&[&old]);
+
+        let comparison = compare(&left, &right, &baseline(&[&old]), SyncPolicy::Ask);
+        assert_eq!(
+            actions(&comparison),
+            vec![("a", SyncAction::CopyToRight, false), ("new", SyncAction::CopyToRight, false)]
+        );
+    }
+
+    #[test]
+    fn deletions_since_baseline_are_propagated() {
+        let gone = record("gone", "1", None);
+        let right = records(&[&gone]);
+
+        let comparison = compare(&HashMap::new(), &right, &baseline(&[&gone]), SyncPolicy::Ask);
+        assert_eq!(actions(&comparison), vec![("gone", SyncAction::DeleteRight, false)]);
+    }
+
+    #[test]
+    fn matching_and_removed_keys_feed_the_baseline() {
+        let same = record("same", "1", None);
+        let left = records(&[&same]);
+        let right = records(&[&same]);
+        let stale = record("stale", "1", None);
+
+        let comparison = compare(&left, &right, &baseline(&[&stale]), SyncPolicy::Ask);
+        assert!(comparison.changes.is_empty());
+        assert_eq!(comparison.in_sync, vec![("same".to_string(), same.fingerprint())]);
+        assert_eq!(comparison.removed, vec!["stale".to_string()]);
+    }
+
+    #[test]
+    fn conflicts_follow_the_policy() {
+        let base = record("k", "0", None);
+        let left = records(&[&record("k", "1", Some(20))]);
+        let right = records(&[&record("k", "2", Some(10))]);
+        let baseline = baseline(&[&base]);
+
+        let policies = [
+            (SyncPolicy::PreferLeft, SyncAction::CopyToRight),
+            (SyncPolicy::PreferRight, SyncAction::CopyToLeft),
+            (SyncPolicy::NewestWins, SyncAction::CopyToRight),
+            (SyncPolicy::Ask, SyncAction::Conflict),
+        ];
+        for (policy, expected) in policies {
+            let comparison = compare(&left, &right, &baseline, policy);
+            assert_eq!(actions(&comparison), vec![("k", expected, true)]);
+        }
+    }
+
+    #[test]
+    fn newest_wins_without_timestamps_is_a_conflict() {
+        let left = records(&[&record("k", "1", None)]);
+        let right = records(&[&record("k", "2", Some(10))]);
+
+        let comparison = compare(&left, &right, &HashMap::new(), SyncPolicy::NewestWins);
+        assert_eq!(actions(&comparison), vec![("k", SyncAction::Conflict, true)]);
+    }
+}
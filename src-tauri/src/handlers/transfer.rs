@@ -0,0 +1,447 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tauri::{command, AppHandle, Manager, State};
+
+use crate::app_state::AppState;
+use crate::handlers::cache::require_online;
+use crate::handlers::local::{delete_local_keys, read_local_records, resolve_namespace, write_local_records};
+use crate::handlers::remote::{
+    connection_for_account, create_remote_namespace, delete_remote_keys, fetch_remote_records, list_all_remote_keys, require_permission, write_remote_records
+};
+use crate::masking::mask_record;
+use crate::models::error::ApiError;
+use crate::models::job::JobId;
+use crate::jobs::JobHandle;
+use crate::models::kv::{KVPermission, RemoteConnection};
+use crate::models::transfer::{
+    CloneCheckpoint, CloneResult, CloneTarget, KVRecord, KeyInfo, NamespaceRef, PlannedWrite, PullOptions, TransferOptions, TransferSummary, WriteAction, WriteMode
+};
+
+/// A namespace on either backend, resolved and permission-checked before a
+/// job starts. Local namespaces are read in a single pass, so their records
+/// are kept once listed.
+pub enum Endpoint {
+    Local { kv_path: PathBuf, namespace_name: String, records: HashMap<String, KVRecord> },
+    Remote { connection: RemoteConnection, namespace_id: String },
+}
+
+impl Endpoint {
+    pub async fn resolve(state: &AppState, namespace: &NamespaceRef, permission: KVPermission) -> Result<Self, ApiError> {
+        match namespace {
+            NamespaceRef::Local { folder_id, namespace_id } => {
+                let (kv_path, namespace_name) = resolve_namespace(state, *folder_id, namespace_id)?;
+                Ok(Endpoint::Local { kv_path, namespace_name, records: HashMap::new() })
+            }
+            NamespaceRef::Remote { account_id, namespace_id } => {
+                if permission != KVPermission::Read {
+                    require_online(state)?;
+                }
+
+                let connection = connection_for_account(state, account_id).await?;
+                require_permission(&connection, KVPermission::Read)?;
+                require_permission(&connection, permission)?;
+
+                Ok(Endpoint::Remote { connection, namespace_id: namespace_id.clone() })
+            }
+        }
+    }
+
+    pub async fn list(&mut self, state: &AppState, job: &JobHandle) -> Result<Vec<KeyInfo>, ApiError> {
+        match self {
+            Endpoint::Local { kv_path, namespace_name, records } => {
+                let (kv_path, namespace_name, read_job) = (kv_path.clone(), namespace_name.clone(), job.clone());
+                let loaded = tauri::async_runtime::spawn_blocking(move || read_local_records(&kv_path, &namespace_name, &read_job))
+                    .await
+                    .map_err(|e| format!("Failed to read local namespace: {}", e))??;
+
+                let keys = loaded.iter().map(KeyInfo::from).collect();
+                *records = loaded.into_iter().map(|record| (record.key.clone(), record)).collect();
+                Ok(keys)
+            }
+            Endpoint::Remote { connection, namespace_id } => Ok(list_all_remote_keys(state, job, connection, namespace_id, None)
+                .await?
+                .into_iter()
+                .map(KeyInfo::from)
+                .collect()),
+        }
+    }
+
+    pub async fn fetch(&self, job: &JobHandle, keys: Vec<KeyInfo>) -> Result<Vec<KVRecord>, ApiError> {
+        match self {
+            Endpoint::Local { records, .. } => Ok(keys.iter().filter_map(|k| records.get(&k.key).cloned()).collect()),
+            Endpoint::Remote { connection, namespace_id } => fetch_remote_records(job, connection, namespace_id, keys).await,
+        }
+    }
+
+    pub async fn write(&self, state: &AppState, job: &JobHandle, records: Vec<KVRecord>) -> Result<usize, ApiError> {
+        match self {
+            Endpoint::Local { kv_path, namespace_name, .. } => {
+                let (kv_path, namespace_name, write_job) = (kv_path.clone(), namespace_name.clone(), job.clone());
+                Ok(tauri::async_runtime::spawn_blocking(move || write_local_records(&kv_path, &namespace_name, &records, &write_job))
+                    .await
+                    .map_err(|e| format!("Failed to write local namespace: {}", e))??)
+            }
+            Endpoint::Remote { connection, namespace_id } => write_remote_records(state, job, connection, namespace_id, &records).await,
+        }
+    }
+
+    pub async fn delete(&self, state: &AppState, job: &JobHandle, keys: Vec<String>) -> Result<usize, ApiError> {
+        match self {
+            Endpoint::Local { kv_path, namespace_name, .. } => {
+                let (kv_path, namespace_name) = (kv_path.clone(), namespace_name.clone());
+                let count = keys.len();
+                tauri::async_runtime::spawn_blocking(move || delete_local_keys(&kv_path, &namespace_name, &keys))
+                    .await
+                    .map_err(|e| format!("Failed to delete local keys: {}", e))??;
+                Ok(count)
+            }
+            Endpoint::Remote { connection, namespace_id } => delete_remote_keys(state, job, connection, namespace_id, &keys).await,
+        }
+    }
+}
+
+const CLONE_BATCH_SIZE: usize = 500;
+
+fn plan_writes(records: &[KVRecord], existing: &HashSet<String>, mode: WriteMode) -> Vec<PlannedWrite> {
+    let now = chrono::Utc::now().timestamp();
+
+    records
+        .iter()
+        .map(|record| {
+            let action = if record.is_expired(now) {
+                WriteAction::Expired
+            } else if !existing.contains(&record.key) {
+                WriteAction::Create
+            } else if mode == WriteMode::SkipExisting {
+                WriteAction::Skip
+            } else {
+                WriteAction::Overwrite
+            };
+
+            PlannedWrite { key: record.key.clone(), action }
+        })
+        .collect()
+}
+
+fn summarize(plan: Vec<PlannedWrite>, written: usize, dry_run: bool) -> TransferSummary {
+    let count = |action: WriteAction| plan.iter().filter(|p| p.action == action).count();
+
+    TransferSummary {
+        total: plan.len(),
+        written,
+        skipped: count(WriteAction::Skip),
+        expired: count(WriteAction::Expired),
+        deleted: count(WriteAction::Delete),
+        dry_run,
+        plan: if dry_run { plan } else { vec![] },
+    }
+}
+
+async fn run_plan(
+    state: &AppState,
+    job: &JobHandle,
+    endpoint: &mut Endpoint,
+    records: Vec<KVRecord>,
+    plan: Vec<PlannedWrite>,
+    dry_run: bool
+) -> Result<TransferSummary, ApiError> {
+    if dry_run {
+        return Ok(summarize(plan, 0, true));
+    }
+
+    let to_write: Vec<KVRecord> = records
+        .into_iter()
+        .zip(&plan)
+        .filter(|(_, planned)| matches!(planned.action, WriteAction::Create | WriteAction::Overwrite))
+        .map(|(record, _)| record)
+        .collect();
+    let written = if to_write.is_empty() { 0 } else { endpoint.write(state, job, to_write).await? };
+
+    let to_delete: Vec<String> = plan
+        .iter()
+        .filter(|planned| planned.action == WriteAction::Delete)
+        .map(|planned| planned.key.clone())
+        .collect();
+    if !to_delete.is_empty() {
+        endpoint.delete(state, job, to_delete).await?;
+    }
+
+    Ok(summarize(plan, written, false))
+}
+
+pub async fn apply_records(
+    state: &AppState,
+    job: &JobHandle,
+    endpoint: &mut Endpoint,
+    records: Vec<KVRecord>,
+    options: TransferOptions
+) -> Result<TransferSummary, ApiError> {
+    let existing: HashSet<String> = endpoint.list(state, job).await?.into_iter().map(|k| k.key).collect();
+    let plan = plan_writes(&records, &existing, options.mode);
+    run_plan(state, job, endpoint, records, plan, options.dry_run).await
+}
+
+/// Like `apply_records`, but also deletes keys in `endpoint` that are not among `records`.
+pub async fn replace_records(
+    state: &AppState,
+    job: &JobHandle,
+    endpoint: &mut Endpoint,
+    records: Vec<KVRecord>,
+    options: TransferOptions
+) -> Result<TransferSummary, ApiError> {
+    let existing: HashSet<String> = endpoint.list(state, job).await?.into_iter().map(|k| k.key).collect();
+    let mut plan = plan_writes(&records, &existing, options.mode);
+
+    let kept: HashSet<&str> = records.iter().map(|r| r.key.as_str()).collect();
+    let mut missing: Vec<&String> = existing.iter().filter(|key| !kept.contains(key.as_str())).collect();
+    missing.sort();
+    plan.extend(missing.into_iter().map(|key| PlannedWrite { key: key.clone(), action: WriteAction::Delete }));
+
+    run_plan(state, job, endpoint, records, plan, options.dry_run).await
+}
+
+#[command]
+pub async fn copy_local_to_remote(
+    app: AppHandle,
+    folder_id: i64,
+    namespace_id: String,
+    account_id: String,
+    target_namespace_id: String,
+    options: TransferOptions,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    let (kv_path, namespace_name) = resolve_namespace(&state, folder_id, &namespace_id)?;
+    let target = NamespaceRef::Remote { account_id, namespace_id: target_namespace_id };
+    let permission = if options.dry_run { KVPermission::Read } else { KVPermission::Write };
+    let mut endpoint = Endpoint::resolve(&state, &target, permission).await?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "copy_local_to_remote", move |job| async move {
+        let state = task_app.state::<AppState>();
+
+        let read_job = job.clone();
+        let records = tauri::async_runtime::spawn_blocking(move || read_local_records(&kv_path, &namespace_name, &read_job))
+            .await
+            .map_err(|e| format!("Failed to read local namespace: {}", e))??;
+
+        apply_records(&state, &job, &mut endpoint, records, options).await
+    });
+
+    Ok(job_id)
+}
+
+#[command]
+pub async fn pull_remote_to_local(
+    app: AppHandle,
+    account_id: String,
+    namespace_id: String,
+    folder_id: i64,
+    target_namespace_id: String,
+    options: PullOptions,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    require_online(&state)?;
+
+    let (kv_path, namespace_name) = resolve_namespace(&state, folder_id, &target_namespace_id)?;
+    let connection = connection_for_account(&state, &account_id).await?;
+
+    require_permission(&connection, KVPermission::Read)?;
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "pull_remote_to_local", move |job| async move {
+        let state = task_app.state::<AppState>();
+
+        let keys = list_all_remote_keys(&state, &job, &connection, &namespace_id, options.prefix.as_deref())
+            .await?
+            .into_iter()
+            .map(KeyInfo::from)
+            .collect();
+        let now = chrono::Utc::now().timestamp();
+        let (mut records, stale): (Vec<KVRecord>, Vec<KVRecord>) = fetch_remote_records(&job, &connection, &namespace_id, keys)
+            .await?
+            .into_iter()
+            .partition(|record| !record.is_expired(now));
+        for record in &mut records {
+            mask_record(record, &options.masks);
+        }
+
+        let total = records.len() + stale.len();
+        let write_job = job.clone();
+        let written = tauri::async_runtime::spawn_blocking(move || write_local_records(&kv_path, &namespace_name, &records, &write_job))
+            .await
+            .map_err(|e| format!("Failed to write local namespace: {}", e))??;
+
+        Ok::<_, ApiError>(TransferSummary {
+            total,
+            written,
+            expired: stale.len(),
+            ..TransferSummary::default()
+        })
+    });
+
+    Ok(job_id)
+}
+
+async fn clone_keys(
+    state: &AppState,
+    job: &JobHandle,
+    (source, source_connection): (NamespaceRef, RemoteConnection),
+    target_connection: RemoteConnection,
+    target: CloneTarget,
+    resume: bool
+) -> Result<CloneResult, ApiError> {
+    let NamespaceRef::Remote { namespace_id: source_namespace_id, .. } = &source else {
+        return Err("Clone source must be a remote namespace".into());
+    };
+
+    let (target_namespace_id, target_title) = match target.namespace_id {
+        Some(namespace_id) => (namespace_id, None),
+        None => {
+            let title = target.title.filter(|t| !t.trim().is_empty())
+                .ok_or_else(|| "A title is required to create the target namespace".to_string())?;
+            let earlier = if resume {
+                let db = state.db.lock().unwrap();
+                db.find_clone_checkpoint_by_title(&source, &target_connection.account_id, &title)
+                    .map_err(|e| format!("Failed to read clone checkpoint: {}", e))?
+            } else {
+                None
+            };
+
+            let namespace_id = match earlier.map(|c| c.target) {
+                Some(NamespaceRef::Remote { namespace_id, .. }) => namespace_id,
+                _ => {
+                    let namespace_id = create_remote_namespace(state, &target_connection, &title).await?.id;
+                    // Recorded before copying so a run that stops early still finds the namespace it created.
+                    let db = state.db.lock().unwrap();
+                    let checkpoint = CloneCheckpoint {
+                        source: source.clone(),
+                        target: NamespaceRef::Remote {
+                            account_id: target_connection.account_id.clone(),
+                            namespace_id: namespace_id.clone(),
+                        },
+                        target_title: Some(title.clone()),
+                        last_key: String::new(),
+                        copied: 0,
+                        updated_at: chrono::Utc::now().timestamp(),
+                    };
+                    if let Err(e) = db.save_clone_checkpoint(&checkpoint) {
+                        eprintln!("Failed to save clone checkpoint: {}", e);
+                    }
+                    namespace_id
+                }
+            };
+            (namespace_id, Some(title))
+        }
+    };
+    let target_ref = NamespaceRef::Remote {
+        account_id: target_connection.account_id.clone(),
+        namespace_id: target_namespace_id.clone(),
+    };
+
+    let checkpoint = {
+        let db = state.db.lock().unwrap();
+        if !resume {
+            if let Err(e) = db.remove_clone_checkpoint(&source, &target_ref) {
+                eprintln!("Failed to remove clone checkpoint: {}", e);
+            }
+        }
+        db.get_clone_checkpoint(&source, &target_ref).map_err(|e| format!("Failed to read clone checkpoint: {}", e))?
+    };
+    let resumed_from = checkpoint.as_ref().map(|c| c.last_key.clone()).filter(|k| !k.is_empty());
+    let mut copied = checkpoint.as_ref().map(|c| c.copied).unwrap_or(0);
+    let mut expired = 0;
+
+    let keys: Vec<KeyInfo> = list_all_remote_keys(state, job, &source_connection, source_namespace_id, None)
+        .await?
+        .into_iter()
+        .map(KeyInfo::from)
+        .filter(|k| resumed_from.as_ref().is_none_or(|last_key| &k.key > last_key))
+        .collect();
+    let total = (copied + keys.len()) as u64;
+
+    for chunk in keys.chunks(CLONE_BATCH_SIZE) {
+        job.ensure_active::<ApiError>()?;
+
+        let now = chrono::Utc::now().timestamp();
+        let (records, stale): (Vec<KVRecord>, Vec<KVRecord>) = fetch_remote_records(job, &source_connection, source_namespace_id, chunk.to_vec())
+            .await?
+            .into_iter()
+            .partition(|record| !record.is_expired(now));
+        expired += stale.len();
+        copied += write_remote_records(state, job, &target_connection, &target_namespace_id, &records).await?;
+
+        if let Some(last) = chunk.last() {
+            let db = state.db.lock().unwrap();
+            let checkpoint = CloneCheckpoint {
+                source: source.clone(),
+                target: target_ref.clone(),
+                target_title: target_title.clone(),
+                last_key: last.key.clone(),
+                copied,
+                updated_at: now,
+            };
+            if let Err(e) = db.save_clone_checkpoint(&checkpoint) {
+                eprintln!("Failed to save clone checkpoint: {}", e);
+            }
+        }
+
+        job.progress(copied as u64, Some(total), Some("Cloning keys".to_string()));
+    }
+
+    {
+        let db = state.db.lock().unwrap();
+        if let Err(e) = db.remove_clone_checkpoint(&source, &target_ref) {
+            eprintln!("Failed to remove clone checkpoint: {}", e);
+        }
+    }
+
+    Ok(CloneResult {
+        namespace_id: target_namespace_id,
+        copied,
+        expired,
+        resumed_from,
+    })
+}
+
+/// Copies every key of a remote namespace into a namespace of another saved
+/// connection. Progress is checkpointed after each batch, so running the same
+/// clone again after a failure or cancellation picks up where it stopped.
+#[command]
+pub async fn clone_namespace(
+    app: AppHandle,
+    source_account_id: String,
+    source_namespace_id: String,
+    target: CloneTarget,
+    resume: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<JobId, ApiError> {
+    require_online(&state)?;
+
+    let source_connection = connection_for_account(&state, &source_account_id).await?;
+    require_permission(&source_connection, KVPermission::Read)?;
+
+    let target_connection = connection_for_account(&state, &target.account_id).await?;
+    require_permission(&target_connection, KVPermission::Write)?;
+    if target.namespace_id.is_none() {
+        require_permission(&target_connection, KVPermission::ManageNamespaces)?;
+    }
+
+    let source = NamespaceRef::Remote {
+        account_id: source_account_id,
+        namespace_id: source_namespace_id,
+    };
+
+    let task_app = app.clone();
+    let job_id = state.jobs.spawn(&app, "clone_namespace", move |job| async move {
+        let state = task_app.state::<AppState>();
+        clone_keys(&state, &job, (source, source_connection), target_connection, target, resume.unwrap_or(true)).await
+    });
+
+    Ok(job_id)
+}
+
+#[command]
+pub fn list_clone_checkpoints(state: State<AppState>) -> Result<Vec<CloneCheckpoint>, ApiError> {
+    let db = state.db.lock().unwrap();
+    db.list_clone_checkpoints().map_err(|e| format!("Failed to read clone checkpoints: {}", e).into())
+}
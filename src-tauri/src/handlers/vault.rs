@@ -0,0 +1,101 @@
+use tauri::{command, State};
+
+use crate::app_state::AppState;
+use crate::models::kv::VaultStatus;
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+fn reload_connections(state: &AppState) -> Result<(), String> {
+    let connections = {
+        let db = state.db.lock().unwrap();
+        db.get_remote_connections()
+            .map_err(|e| format!("Failed to load remote connections: {}", e))?
+    };
+
+    let mut remote_connections = state.remote_connections.lock().unwrap();
+    *remote_connections = connections;
+
+    Ok(())
+}
+
+fn check_password_strength(password: &str) -> Result<(), String> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Master password must be at least {} characters", MIN_PASSWORD_LENGTH));
+    }
+
+    Ok(())
+}
+
+#[command]
+pub fn get_vault_status(state: State<AppState>) -> Result<VaultStatus, String> {
+    let db = state.db.lock().unwrap();
+    let configured = db.is_vault_configured()
+        .map_err(|e| format!("Failed to read vault status: {}", e))?;
+
+    let required = !configured
+        && db.has_unencrypted_secrets()
+            .map_err(|e| format!("Failed to read vault status: {}", e))?;
+
+    Ok(VaultStatus {
+        configured,
+        unlocked: db.is_vault_unlocked(),
+        required,
+    })
+}
+
+#[command]
+pub async fn create_master_password(password: String, state: State<'_, AppState>) -> Result<(), String> {
+    check_password_strength(&password)?;
+
+    {
+        let mut db = state.db.lock().unwrap();
+        if let Err(e) = db.create_vault(&password) {
+            return Err(format!("Failed to create master password: {}", e));
+        }
+    }
+
+    reload_connections(&state)
+}
+
+#[command]
+pub async fn unlock_vault(password: String, state: State<'_, AppState>) -> Result<(), String> {
+    {
+        let mut db = state.db.lock().unwrap();
+        if let Err(e) = db.unlock_vault(&password) {
+            return Err(e.to_string());
+        }
+    }
+
+    reload_connections(&state)
+}
+
+#[command]
+pub fn lock_vault(state: State<AppState>) -> Result<(), String> {
+    {
+        let mut db = state.db.lock().unwrap();
+        db.lock_vault();
+    }
+
+    {
+        let mut connections = state.remote_connections.lock().unwrap();
+        connections.clear();
+    }
+
+    Ok(())
+}
+
+#[command]
+pub async fn change_master_password(
+    current_password: String,
+    new_password: String,
+    state: State<'_, AppState>
+) -> Result<(), String> {
+    check_password_strength(&new_password)?;
+
+    let mut db = state.db.lock().unwrap();
+    if let Err(e) = db.change_master_password(&current_password, &new_password) {
+        return Err(format!("Failed to change master password: {}", e));
+    }
+
+    Ok(())
+}
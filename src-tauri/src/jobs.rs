@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::models::job::{JobId, JobInfo, JobProgress, JobProgressEvent, JobStatus};
+
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+pub const JOB_FINISHED_EVENT: &str = "job-finished";
+const MAX_FINISHED_JOBS: usize = 50;
+const FINISHED_JOB_TTL_SECONDS: i64 = 10 * 60;
+
+struct JobEntry {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+type JobTable = Arc<Mutex<HashMap<JobId, JobEntry>>>;
+
+/// Finished jobs hold their whole result, so only the most recent ones are
+/// kept and only for a while after they finish.
+fn prune_finished(jobs: &mut HashMap<JobId, JobEntry>, now: i64) {
+    jobs.retain(|_, entry| entry.info.finished_at.is_none_or(|at| now - at < FINISHED_JOB_TTL_SECONDS));
+
+    let mut finished: Vec<(JobId, i64)> = jobs
+        .values()
+        .filter_map(|entry| entry.info.finished_at.map(|at| (entry.info.id, at)))
+        .collect();
+    if finished.len() > MAX_FINISHED_JOBS {
+        finished.sort_by_key(|(id, at)| (*at, *id));
+        for (id, _) in finished.iter().take(finished.len() - MAX_FINISHED_JOBS) {
+            jobs.remove(id);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct JobManager {
+    next_id: AtomicU64,
+    jobs: JobTable,
+}
+
+#[derive(Clone)]
+pub struct JobHandle {
+    id: JobId,
+    app: AppHandle,
+    cancelled: Arc<AtomicBool>,
+    jobs: JobTable,
+}
+
+impl JobManager {
+    pub fn spawn<F, Fut, T, E>(&self, app: &AppHandle, kind: &str, task: F) -> JobId
+    where
+        F: FnOnce(JobHandle) -> Fut,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        T: Serialize + Send + 'static,
+        E: Serialize + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let mut jobs = self.jobs.lock().unwrap();
+        prune_finished(&mut jobs, chrono::Utc::now().timestamp());
+        jobs.insert(id, JobEntry {
+            info: JobInfo {
+                id,
+                kind: kind.to_string(),
+                status: JobStatus::Running,
+                progress: JobProgress::default(),
+                result: None,
+                error: None,
+                started_at: chrono::Utc::now().timestamp(),
+                finished_at: None,
+            },
+            cancelled: cancelled.clone(),
+        });
+        drop(jobs);
+
+        let handle = JobHandle {
+            id,
+            app: app.clone(),
+            cancelled,
+            jobs: self.jobs.clone(),
+        };
+
+        let future = task(handle.clone());
+        tauri::async_runtime::spawn(async move {
+            let outcome = future.await;
+            handle.finish(outcome);
+        });
+
+        id
+    }
+
+    pub fn cancel(&self, id: JobId) -> Result<(), String> {
+        let jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get(&id).ok_or_else(|| "Job not found".to_string())?;
+
+        if entry.info.status != JobStatus::Running {
+            return Err("Job has already finished".to_string());
+        }
+
+        entry.cancelled.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub fn get(&self, id: JobId) -> Option<JobInfo> {
+        self.jobs.lock().unwrap().get(&id).map(|entry| entry.info.clone())
+    }
+
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self.jobs.lock().unwrap().values().map(|entry| entry.info.clone()).collect();
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.id));
+        jobs
+    }
+}
+
+impl JobHandle {
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Err(e) = self.app.emit_all(event, payload) {
+            eprintln!("Failed to emit {}: {}", event, e);
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn ensure_active<E: From<String>>(&self) -> Result<(), E> {
+        if self.is_cancelled() {
+            return Err(E::from("Job was cancelled".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn progress(&self, completed: u64, total: Option<u64>, message: Option<String>) {
+        let progress = JobProgress { completed, total, message };
+
+        if let Some(entry) = self.jobs.lock().unwrap().get_mut(&self.id) {
+            entry.info.progress = progress.clone();
+        }
+
+        let event = JobProgressEvent { job_id: self.id, progress };
+        if let Err(e) = self.app.emit_all(JOB_PROGRESS_EVENT, event) {
+            eprintln!("Failed to emit job progress: {}", e);
+        }
+    }
+
+    fn finish<T: Serialize, E: Serialize>(&self, outcome: Result<T, E>) {
+        let info = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let entry = match jobs.get_mut(&self.id) {
+                Some(entry) => entry,
+                None => return,
+            };
+
+            match outcome {
+                Ok(result) => {
+                    entry.info.status = JobStatus::Completed;
+                    entry.info.result = serde_json::to_value(result).ok();
+                }
+                Err(error) => {
+                    entry.info.status = if self.is_cancelled() { JobStatus::Cancelled } else { JobStatus::Failed };
+                    entry.info.error = serde_json::to_value(error).ok();
+                }
+            }
+            let now = chrono::Utc::now().timestamp();
+            entry.info.finished_at = Some(now);
+            let info = entry.info.clone();
+
+            prune_finished(&mut jobs, now);
+            info
+        };
+
+        if let Err(e) = self.app.emit_all(JOB_FINISHED_EVENT, info) {
+            eprintln!("Failed to emit job result: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: JobId, finished_at: Option<i64>) -> JobEntry {
+        JobEntry {
+            info: JobInfo {
+                id,
+                kind: "test".to_string(),
+                status: if finished_at.is_some() { JobStatus::Completed } else { JobStatus::Running },
+                progress: JobProgress::default(),
+                result: None,
+                error: None,
+                started_at: 0,
+                finished_at,
+            },
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[test]
+    fn prunes_expired_and_excess_finished_jobs() {
+        let now = 10_000;
+        let mut jobs: HashMap<JobId, JobEntry> = HashMap::new();
+        jobs.insert(1, entry(1, None));
+        jobs.insert(2, entry(2, Some(now - FINISHED_JOB_TTL_SECONDS)));
+        for id in 3..(3 + MAX_FINISHED_JOBS as JobId + 5) {
+            jobs.insert(id, entry(id, Some(now - 1)));
+        }
+
+        prune_finished(&mut jobs, now);
+
+        assert!(jobs.contains_key(&1));
+        assert!(!jobs.contains_key(&2));
+        assert!(!jobs.contains_key(&3));
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 1);
+    }
+}
@@ -0,0 +1,134 @@
+use serde_json::Value;
+
+use crate::models::diff::{JsonChange, JsonChangeKind};
+
+pub const MAX_JSON_CHANGES: usize = 200;
+
+fn child_path(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.to_string()
+    } else {
+        format!("{}.{}", path, segment)
+    }
+}
+
+fn walk(left: &Value, right: &Value, path: &str, changes: &mut Vec<JsonChange>) {
+    if changes.len() > MAX_JSON_CHANGES {
+        return;
+    }
+
+    match (left, right) {
+        (Value::Object(left_map), Value::Object(right_map)) => {
+            for (key, left_value) in left_map {
+                match right_map.get(key) {
+                    Some(right_value) => walk(left_value, right_value, &child_path(path, key), changes),
+                    None => changes.push(JsonChange {
+                        path: child_path(path, key),
+                        kind: JsonChangeKind::Removed,
+                        left: Some(left_value.clone()),
+                        right: None,
+                    }),
+                }
+            }
+            for (key, right_value) in right_map {
+                if !left_map.contains_key(key) {
+                    changes.push(JsonChange {
+                        path: child_path(path, key),
+                        kind: JsonChangeKind::Added,
+                        left: None,
+                        right: Some(right_value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(left_items), Value::Array(right_items)) => {
+            for index in 0..left_items.len().max(right_items.len()) {
+                let item_path = child_path(path, &index.to_string());
+                match (left_items.get(index), right_items.get(index)) {
+                    (Some(l), Some(r)) => walk(l, r, &item_path, changes),
+                    (Some(l), None) => changes.push(JsonChange {
+                        path: item_path,
+                        kind: JsonChangeKind::Removed,
+                        left: Some(l.clone()),
+                        right: None,
+                    }),
+                    (None, Some(r)) => changes.push(JsonChange {
+                        path: item_path,
+                        kind: JsonChangeKind::Added,
+                        left: None,
+                        right: Some(r.clone()),
+                    }),
+                    (None, None) => {}
+                }
+            }
+        }
+        (l, r) if l != r => changes.push(JsonChange {
+            path: path.to_string(),
+            kind: JsonChangeKind::Changed,
+            left: Some(l.clone()),
+            right: Some(r.clone()),
+        }),
+        _ => {}
+    }
+}
+
+/// Structural diff of two JSON documents. Arrays are compared by index.
+/// Returns the changes and whether the list was cut off at `MAX_JSON_CHANGES`.
+pub fn diff_json(left: &Value, right: &Value) -> (Vec<JsonChange>, bool) {
+    let mut changes = Vec::new();
+    walk(left, right, "", &mut changes);
+
+    let truncated = changes.len() > MAX_JSON_CHANGES;
+    changes.truncate(MAX_JSON_CHANGES);
+    (changes, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summary(changes: &[JsonChange]) -> Vec<(&str, JsonChangeKind)> {
+        let mut summary: Vec<_> = changes.iter().map(|c| (c.path.as_str(), c.kind)).collect();
+        summary.sort_by_key(|(path, _)| *path);
+        summary
+    }
+
+    #[test]
+    fn reports_nested_field_changes_by_path() {
+        let left = json!({"name": "a", "tags": ["x", "y"], "owner": {"id": 1, "role": "admin"}});
+        let right = json!({"name": "b", "tags": ["x"], "owner": {"id": 1, "team": "ops"}});
+
+        let (changes, truncated) = diff_json(&left, &right);
+        assert!(!truncated);
+        assert_eq!(summary(&changes), vec![
+            ("name", JsonChangeKind::Changed),
+            ("owner.role", JsonChangeKind::Removed),
+            ("owner.team", JsonChangeKind::Added),
+            ("tags.1", JsonChangeKind::Removed),
+        ]);
+        let name = changes.iter().find(|c| c.path == "name").unwrap();
+        assert_eq!((name.left.clone(), name.right.clone()), (Some(json!("a")), Some(json!("b"))));
+    }
+
+    #[test]
+    fn a_changed_root_has_an_empty_path() {
+        let (changes, _) = diff_json(&json!([1]), &json!({"a": 1}));
+        assert_eq!(summary(&changes), vec![("", JsonChangeKind::Changed)]);
+
+        assert!(diff_json(&json!({"a": [1, {"b": null}]}), &json!({"a": [1, {"b": null}]})).0.is_empty());
+    }
+
+    #[test]
+    fn long_change_lists_are_truncated() {
+        let left = Value::Array((0..MAX_JSON_CHANGES as u64 + 10).map(Value::from).collect());
+        let (changes, truncated) = diff_json(&left, &json!([]));
+        assert!(truncated);
+        assert_eq!(changes.len(), MAX_JSON_CHANGES);
+
+        let left = Value::Array((0..MAX_JSON_CHANGES as u64).map(Value::from).collect());
+        let (changes, truncated) = diff_json(&left, &json!([]));
+        assert!(!truncated);
+        assert_eq!(changes.len(), MAX_JSON_CHANGES);
+    }
+}
@@ -7,13 +7,32 @@ mod app_state;
 mod models;
 mod handlers;
 mod persistence;
+mod credentials;
+mod vault;
+mod jobs;
+mod rate_limit;
+mod masking;
+mod json_diff;
+mod search;
 
 use app_state::AppState;
 use tauri::Manager;
 use handlers::local::{add_folder, remove_folder, load_folder, get_folders, update_kv, delete_kv};
+use handlers::jobs::{list_jobs, get_job, cancel_job};
+use handlers::cache::{get_remote_cache_status, clear_remote_cache};
+use handlers::transfer::{copy_local_to_remote, pull_remote_to_local, clone_namespace, list_clone_checkpoints};
+use handlers::diff::diff_namespaces;
+use handlers::sync::{plan_sync, apply_sync};
+use handlers::export::{export_bulk_json, export_directory, export_ndjson};
+use handlers::import::{import_keys, import_directory};
+use handlers::search::search_values;
+use handlers::vault::{get_vault_status, create_master_password, unlock_vault, lock_vault, change_master_password};
 use handlers::remote::{
-    connect_cloudflare, get_remote_namespaces, get_remote_keys,
-    get_remote_value, update_remote_kv, delete_remote_kv, disconnect_cloudflare
+    connect_cloudflare, discover_cloudflare_accounts, connect_cloudflare_accounts,
+    load_external_connections, list_remote_connections, rename_remote_connection, replace_remote_connection_token,
+    disconnect_remote_connection, get_rate_limit_budgets, get_remote_namespaces, get_remote_keys, list_remote_keys,
+    get_remote_value, get_remote_values, open_remote_value, get_remote_metadata, get_remote_keys_metadata,
+    update_remote_kv, delete_remote_kv, disconnect_cloudflare
 };
 
 fn main() {
@@ -37,6 +56,11 @@ fn main() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            get_vault_status,
+            create_master_password,
+            unlock_vault,
+            lock_vault,
+            change_master_password,
             get_folders,
             add_folder,
             remove_folder,
@@ -44,12 +68,43 @@ fn main() {
             update_kv,
             delete_kv,
             connect_cloudflare,
+            discover_cloudflare_accounts,
+            connect_cloudflare_accounts,
             disconnect_cloudflare,
+            load_external_connections,
+            list_remote_connections,
+            rename_remote_connection,
+            replace_remote_connection_token,
+            disconnect_remote_connection,
+            get_rate_limit_budgets,
             get_remote_namespaces,
             get_remote_keys,
             get_remote_value,
+            get_remote_values,
+            open_remote_value,
+            get_remote_metadata,
+            get_remote_keys_metadata,
             update_remote_kv,
             delete_remote_kv,
+            get_remote_cache_status,
+            clear_remote_cache,
+            list_jobs,
+            get_job,
+            cancel_job,
+            list_remote_keys,
+            copy_local_to_remote,
+            pull_remote_to_local,
+            diff_namespaces,
+            plan_sync,
+            apply_sync,
+            clone_namespace,
+            list_clone_checkpoints,
+            export_bulk_json,
+            import_keys,
+            export_directory,
+            import_directory,
+            export_ndjson,
+            search_values
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,145 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::models::transfer::{KVRecord, MaskRule, MaskStrategy};
+use crate::models::value::{KVValue, ValueKind};
+
+const REDACTED: &str = "[REDACTED]";
+
+fn mask_value(value: &mut Value, strategy: MaskStrategy) {
+    *value = match strategy {
+        MaskStrategy::Redact | MaskStrategy::Remove => Value::String(REDACTED.to_string()),
+        MaskStrategy::Hash => {
+            let text = match &*value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let digest = Sha256::digest(text.as_bytes());
+            Value::String(digest.iter().map(|b| format!("{:02x}", b)).collect())
+        }
+    };
+}
+
+/// A `*` segment matches every key of an object or element of an array.
+fn apply_path(value: &mut Value, path: &[&str], strategy: MaskStrategy) -> bool {
+    let (segment, rest) = match path.split_first() {
+        Some(split) => split,
+        None => return false,
+    };
+
+    if rest.is_empty() && strategy == MaskStrategy::Remove {
+        if let Value::Object(map) = value {
+            return if *segment == "*" {
+                let changed = !map.is_empty();
+                map.clear();
+                changed
+            } else {
+                map.remove(*segment).is_some()
+            };
+        }
+    }
+
+    let children: Vec<&mut Value> = match value {
+        Value::Object(map) if *segment == "*" => map.values_mut().collect(),
+        Value::Object(map) => map.get_mut(*segment).into_iter().collect(),
+        Value::Array(items) if *segment == "*" => items.iter_mut().collect(),
+        Value::Array(items) => segment
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| items.get_mut(index))
+            .into_iter()
+            .collect(),
+        _ => vec![],
+    };
+
+    let mut changed = false;
+    for child in children {
+        if rest.is_empty() {
+            mask_value(child, strategy);
+            changed = true;
+        } else {
+            changed |= apply_path(child, rest, strategy);
+        }
+    }
+
+    changed
+}
+
+pub fn apply_masks(value: &mut Value, rules: &[MaskRule]) -> bool {
+    let mut changed = false;
+    for rule in rules {
+        let path: Vec<&str> = rule.field.split('.').filter(|s| !s.is_empty()).collect();
+        changed |= apply_path(value, &path, rule.strategy);
+    }
+    changed
+}
+
+pub fn mask_record(record: &mut KVRecord, rules: &[MaskRule]) {
+    if rules.is_empty() {
+        return;
+    }
+
+    if record.value.kind == ValueKind::Json {
+        if let Ok(mut parsed) = serde_json::from_slice::<Value>(&record.value.bytes) {
+            if apply_masks(&mut parsed, rules) {
+                if let Ok(bytes) = serde_json::to_vec(&parsed) {
+                    record.value = KVValue::from_bytes(bytes);
+                }
+            }
+        }
+    }
+
+    if let Some(metadata) = record.metadata.as_mut() {
+        apply_masks(metadata, rules);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(field: &str, strategy: MaskStrategy) -> MaskRule {
+        MaskRule { field: field.to_string(), strategy }
+    }
+
+    #[test]
+    fn removes_object_fields() {
+        let mut value = json!({ "user": { "email": "a@b.c", "name": "Ann" } });
+        assert!(apply_masks(&mut value, &[rule("user.email", MaskStrategy::Remove)]));
+        assert_eq!(value, json!({ "user": { "name": "Ann" } }));
+    }
+
+    #[test]
+    fn redacts_array_elements_on_remove() {
+        let mut value = json!({ "tokens": ["a", "b", "c"] });
+        assert!(apply_masks(&mut value, &[rule("tokens.1", MaskStrategy::Remove)]));
+        assert_eq!(value, json!({ "tokens": ["a", REDACTED, "c"] }));
+
+        assert!(apply_masks(&mut value, &[rule("tokens.*", MaskStrategy::Remove)]));
+        assert_eq!(value, json!({ "tokens": [REDACTED, REDACTED, REDACTED] }));
+    }
+
+    #[test]
+    fn wildcard_walks_arrays() {
+        let mut value = json!({ "items": [{ "token": "x" }, { "token": "y", "id": 1 }] });
+        assert!(apply_masks(&mut value, &[rule("items.*.token", MaskStrategy::Redact)]));
+        assert_eq!(value, json!({ "items": [{ "token": REDACTED }, { "token": REDACTED, "id": 1 }] }));
+    }
+
+    #[test]
+    fn hashes_values() {
+        let mut value = json!({ "id": "user-1" });
+        assert!(apply_masks(&mut value, &[rule("id", MaskStrategy::Hash)]));
+        let hashed = value["id"].as_str().unwrap();
+        assert_eq!(hashed.len(), 64);
+        assert_ne!(hashed, "user-1");
+    }
+
+    #[test]
+    fn missing_paths_change_nothing() {
+        let mut value = json!({ "a": 1 });
+        assert!(!apply_masks(&mut value, &[rule("b.c", MaskStrategy::Remove), rule("a.0", MaskStrategy::Redact)]));
+        assert_eq!(value, json!({ "a": 1 }));
+    }
+}
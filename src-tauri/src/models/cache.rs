@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheKind {
+    Namespaces,
+    Keys,
+    Metadata,
+    Value,
+}
+
+impl CacheKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheKind::Namespaces => "namespaces",
+            CacheKind::Keys => "keys",
+            CacheKind::Metadata => "metadata",
+            CacheKind::Value => "value",
+        }
+    }
+
+    pub fn ttl_seconds(&self) -> i64 {
+        match self {
+            CacheKind::Namespaces => 600,
+            CacheKind::Keys => 300,
+            CacheKind::Metadata => 300,
+            CacheKind::Value => 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheKey {
+    pub kind: CacheKind,
+    pub account_id: String,
+    pub namespace_id: String,
+    pub key: String,
+}
+
+impl CacheKey {
+    pub fn namespaces(account_id: &str) -> Self {
+        CacheKey::new(CacheKind::Namespaces, account_id, "", "")
+    }
+
+    pub fn keys(account_id: &str, namespace_id: &str, cursor: Option<&str>) -> Self {
+        CacheKey::new(CacheKind::Keys, account_id, namespace_id, cursor.unwrap_or(""))
+    }
+
+    pub fn metadata(account_id: &str, namespace_id: &str, key_name: &str) -> Self {
+        CacheKey::new(CacheKind::Metadata, account_id, namespace_id, key_name)
+    }
+
+    pub fn value(account_id: &str, namespace_id: &str, key_name: &str) -> Self {
+        CacheKey::new(CacheKind::Value, account_id, namespace_id, key_name)
+    }
+
+    fn new(kind: CacheKind, account_id: &str, namespace_id: &str, key: &str) -> Self {
+        CacheKey {
+            kind,
+            account_id: account_id.to_string(),
+            namespace_id: namespace_id.to_string(),
+            key: key.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub payload: String,
+    pub synced_at: i64,
+    pub expires_at: i64,
+}
+
+impl CacheEntry {
+    pub fn is_fresh(&self) -> bool {
+        self.expires_at > chrono::Utc::now().timestamp()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheStatus {
+    pub offline: bool,
+    pub entries: i64,
+    pub last_synced: Option<i64>,
+}
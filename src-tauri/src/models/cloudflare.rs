@@ -1,40 +1,175 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use crate::models::kv::KVEntry;
+use crate::models::transfer::{KVRecord, MIN_EXPIRATION_SECONDS};
+use crate::models::value::KVValue;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CloudflareNamespace {
     pub id: String,
     pub title: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloudflareAccount {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CloudflareResultInfo {
     pub count: u64,
     pub cursor: Option<String>,
+    #[serde(default)]
+    pub page: Option<u64>,
+    #[serde(default)]
+    pub total_pages: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CloudflareListResponse<T> {
     pub success: bool,
     pub errors: Vec<CloudflareError>,
-    pub messages: Vec<String>,
+    #[serde(default)]
+    pub messages: Vec<Value>,
     pub result: Vec<T>,
     pub result_info: Option<CloudflareResultInfo>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareResponse<T> {
+    pub success: bool,
+    pub errors: Vec<CloudflareError>,
+    #[serde(default)]
+    pub messages: Vec<Value>,
+    pub result: Option<T>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareErrorEnvelope {
+    #[serde(default)]
+    pub errors: Vec<CloudflareError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CloudflareError {
     pub code: i32,
     pub message: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareTokenStatus {
+    pub id: String,
+    pub status: String,
+    pub expires_on: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareToken {
+    pub id: String,
+    pub policies: Vec<CloudflareTokenPolicy>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareTokenPolicy {
+    pub effect: String,
+    pub resources: HashMap<String, Value>,
+    pub permission_groups: Vec<CloudflarePermissionGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflarePermissionGroup {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareBulkGetResult {
+    pub values: HashMap<String, Option<String>>,
+}
+
+/// One pair of a bulk write. This is also the file format of
+/// `wrangler kv bulk put`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareBulkWrite {
+    pub key: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_ttl: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(default)]
+    pub base64: bool,
+}
+
+impl From<&KVRecord> for CloudflareBulkWrite {
+    fn from(record: &KVRecord) -> Self {
+        let (value, base64) = match std::str::from_utf8(&record.value.bytes) {
+            Ok(text) => (text.to_string(), false),
+            Err(_) => (STANDARD.encode(&record.value.bytes), true),
+        };
+
+        CloudflareBulkWrite {
+            key: record.key.clone(),
+            value,
+            expiration: record.expiration,
+            expiration_ttl: None,
+            metadata: record.metadata.clone(),
+            base64,
+        }
+    }
+}
+
+impl CloudflareBulkWrite {
+    pub fn into_record(self, now: i64) -> Result<KVRecord, String> {
+        let bytes = if self.base64 {
+            STANDARD.decode(&self.value).map_err(|e| format!("Value is not valid base64: {}", e))?
+        } else {
+            self.value.into_bytes()
+        };
+
+        let expiration = match (self.expiration, self.expiration_ttl) {
+            (Some(expiration), _) => Some(expiration),
+            (None, Some(ttl)) if ttl < MIN_EXPIRATION_SECONDS => {
+                return Err(format!("expiration_ttl must be at least {} seconds", MIN_EXPIRATION_SECONDS));
+            }
+            (None, Some(ttl)) => Some(now + ttl),
+            (None, None) => None,
+        };
+
+        Ok(KVRecord {
+            key: self.key,
+            value: KVValue::from_bytes(bytes),
+            expiration,
+            metadata: self.metadata,
+            modified_at: None,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CloudflareBulkWriteResult {
+    #[serde(default)]
+    pub unsuccessful_keys: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CloudflareKeysResponse {
     pub entries: Vec<KVEntry>,
     pub cursor: Option<String>,
     pub total: u64,
+    #[serde(default)]
+    pub synced_at: Option<i64>,
+    #[serde(default)]
+    pub offline: bool,
 }
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CloudflareKey {
     pub name: String,
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::job::JobId;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    OnlyLeft,
+    OnlyRight,
+    Changed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JsonChange {
+    /// Dot-separated path to the field, empty for the document root.
+    pub path: String,
+    pub kind: JsonChangeKind,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyDiff {
+    pub key: String,
+    pub status: DiffStatus,
+    pub value_changed: bool,
+    pub metadata_changed: bool,
+    pub expiration_changed: bool,
+    pub left_expiration: Option<i64>,
+    pub right_expiration: Option<i64>,
+    pub left_metadata: Option<Value>,
+    pub right_metadata: Option<Value>,
+    /// Field-level changes when both values are JSON.
+    pub json_changes: Vec<JsonChange>,
+    pub json_changes_truncated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DiffSummary {
+    pub only_left: usize,
+    pub only_right: usize,
+    pub changed: usize,
+    pub identical: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiffBatchEvent {
+    pub job_id: JobId,
+    pub diffs: Vec<KeyDiff>,
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::cloudflare::CloudflareError;
+use crate::models::value::{KVValue, RemoteValueSnapshot};
+
+const AUTH_ERROR_CODES: &[i32] = &[6003, 6111, 9103, 9106, 9109, 10000, 10001];
+const KEY_NOT_FOUND_CODE: i32 = 10009;
+const NAMESPACE_NOT_FOUND_CODE: i32 = 10013;
+const NAMESPACE_TITLE_EXISTS_CODE: i32 = 10014;
+const RATE_LIMITED_CODE: i32 = 971;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ApiError {
+    KeyNotFound { message: String },
+    NamespaceNotFound { message: String },
+    NamespaceExists { message: String },
+    InvalidAuth { message: String },
+    PermissionDenied { message: String },
+    PermissionUnknown { message: String },
+    RateLimited { message: String, retry_after: Option<u64> },
+    ValueTooLarge { message: String },
+    KeyTooLong { message: String },
+    Conflict { message: String, remote: Option<Box<RemoteValueSnapshot>>, local: Box<KVValue> },
+    InvalidRequest { message: String, code: Option<i32> },
+    NetworkUnavailable { message: String },
+    ServerError { message: String, status: u16 },
+    Other { message: String, code: Option<i32>, status: Option<u16> },
+}
+
+impl ApiError {
+    pub fn from_response(status: u16, errors: &[CloudflareError], retry_after: Option<u64>) -> Self {
+        let code = errors.first().map(|e| e.code);
+        let detail = if errors.is_empty() {
+            format!("status {}", status)
+        } else {
+            errors
+                .iter()
+                .map(|e| format!("{}: {}", e.code, e.message))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let message = |hint: &str| format!("{} ({})", hint, detail);
+
+        match (code, status) {
+            (Some(KEY_NOT_FOUND_CODE), _) => ApiError::KeyNotFound {
+                message: message("Key not found. It may have been deleted or expired"),
+            },
+            (Some(NAMESPACE_NOT_FOUND_CODE), _) => ApiError::NamespaceNotFound {
+                message: message("Namespace not found. It may have been deleted; refresh the namespace list"),
+            },
+            (Some(NAMESPACE_TITLE_EXISTS_CODE), _) => ApiError::NamespaceExists {
+                message: message("A namespace with this title already exists"),
+            },
+            (Some(RATE_LIMITED_CODE), _) | (_, 429) => ApiError::RateLimited {
+                message: match retry_after {
+                    Some(seconds) => message(&format!("Cloudflare rate limit reached. Try again in {} seconds", seconds)),
+                    None => message("Cloudflare rate limit reached. Wait a moment and try again"),
+                },
+                retry_after,
+            },
+            (Some(code), _) if AUTH_ERROR_CODES.contains(&code) => ApiError::InvalidAuth {
+                message: message("Cloudflare rejected the credentials. Replace the API token for this connection"),
+            },
+            (_, 401) => ApiError::InvalidAuth {
+                message: message("Cloudflare rejected the credentials. Replace the API token for this connection"),
+            },
+            (_, 403) => ApiError::PermissionDenied {
+                message: message("The API token is not allowed to perform this operation"),
+            },
+            (_, 404) if detail.to_lowercase().contains("namespace") => ApiError::NamespaceNotFound {
+                message: message("Namespace not found. It may have been deleted; refresh the namespace list"),
+            },
+            (_, 404) => ApiError::KeyNotFound {
+                message: message("Key not found. It may have been deleted or expired"),
+            },
+            (_, 413) => ApiError::ValueTooLarge {
+                message: message("Value is too large. Workers KV values are limited to 25 MiB"),
+            },
+            (_, 414) => ApiError::KeyTooLong {
+                message: message("Key name is too long. Workers KV keys are limited to 512 bytes"),
+            },
+            (code, 400..=499) => ApiError::InvalidRequest {
+                message: message("Cloudflare rejected the request"),
+                code,
+            },
+            (_, 500..=599) => ApiError::ServerError {
+                message: message("Cloudflare returned a server error. Try again later"),
+                status,
+            },
+            (code, status) => ApiError::Other {
+                message: message("API request failed"),
+                code,
+                status: Some(status),
+            },
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            ApiError::KeyNotFound { message }
+            | ApiError::NamespaceNotFound { message }
+            | ApiError::NamespaceExists { message }
+            | ApiError::InvalidAuth { message }
+            | ApiError::PermissionDenied { message }
+            | ApiError::PermissionUnknown { message }
+            | ApiError::RateLimited { message, .. }
+            | ApiError::ValueTooLarge { message }
+            | ApiError::KeyTooLong { message }
+            | ApiError::Conflict { message, .. }
+            | ApiError::InvalidRequest { message, .. }
+            | ApiError::NetworkUnavailable { message }
+            | ApiError::ServerError { message, .. }
+            | ApiError::Other { message, .. } => message,
+        }
+    }
+
+    pub fn is_network(&self) -> bool {
+        matches!(self, ApiError::NetworkUnavailable { .. })
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl From<String> for ApiError {
+    fn from(message: String) -> Self {
+        ApiError::Other { message, code: None, status: None }
+    }
+}
+
+impl From<&str> for ApiError {
+    fn from(message: &str) -> Self {
+        ApiError::from(message.to_string())
+    }
+}
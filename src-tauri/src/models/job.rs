@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub type JobId = u64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JobProgress {
+    pub completed: u64,
+    pub total: Option<u64>,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobInfo {
+    pub id: JobId,
+    pub kind: String,
+    pub status: JobStatus,
+    pub progress: JobProgress,
+    pub result: Option<Value>,
+    pub error: Option<Value>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JobProgressEvent {
+    pub job_id: JobId,
+    #[serde(flatten)]
+    pub progress: JobProgress,
+}
@@ -2,10 +2,151 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::path::PathBuf;
 
+use crate::models::error::ApiError;
+
+pub const REAUTH_FAILURE_THRESHOLD: u32 = 3;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RemoteConnection {
+    pub id: i64,
+    pub account_id: String,
+    pub account_name: Option<String>,
+    pub label: String,
+    pub auth: ApiAuth,
+    pub permissions: TokenPermissions,
+    pub source: CredentialSource,
+    #[serde(default)]
+    pub failure_count: u32,
+}
+
+impl RemoteConnection {
+    pub fn needs_reauth(&self) -> bool {
+        self.failure_count >= REAUTH_FAILURE_THRESHOLD
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "scheme", rename_all = "snake_case")]
+pub enum ApiAuth {
+    Bearer { token: String },
+    GlobalKey { email: String, key: String },
+}
+
+impl ApiAuth {
+    pub fn new(api_token: String, auth_email: Option<String>) -> Self {
+        match auth_email.map(|e| e.trim().to_string()).filter(|e| !e.is_empty()) {
+            Some(email) => ApiAuth::GlobalKey { email, key: api_token },
+            None => ApiAuth::Bearer { token: api_token },
+        }
+    }
+
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            ApiAuth::Bearer { .. } => "bearer",
+            ApiAuth::GlobalKey { .. } => "global_key",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    #[default]
+    Manual,
+    Wrangler,
+    Environment,
+}
+
+impl CredentialSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CredentialSource::Manual => "manual",
+            CredentialSource::Wrangler => "wrangler",
+            CredentialSource::Environment => "environment",
+        }
+    }
+
+    pub fn from_str(source: &str) -> Self {
+        match source {
+            "wrangler" => CredentialSource::Wrangler,
+            "environment" => CredentialSource::Environment,
+            _ => CredentialSource::Manual,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KVPermission {
+    Read,
+    Write,
+    ManageNamespaces,
+}
+
+/// What the stored token may do on its account. `None` means the token could
+/// not report its own policies, so the operation is attempted and left to the API.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenPermissions {
+    pub read: Option<bool>,
+    pub write: Option<bool>,
+    pub manage_namespaces: Option<bool>,
+}
+
+impl TokenPermissions {
+    pub fn allows(&self, permission: KVPermission) -> Option<bool> {
+        match permission {
+            KVPermission::Read => self.read,
+            KVPermission::Write => self.write,
+            KVPermission::ManageNamespaces => self.manage_namespaces,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteConnectionInfo {
+    pub id: i64,
+    pub account_id: String,
+    pub account_name: Option<String>,
+    pub label: String,
+    pub permissions: TokenPermissions,
+    pub source: CredentialSource,
+    pub auth_scheme: String,
+    pub needs_reauth: bool,
+    pub permissions_unknown: bool,
+}
+
+impl From<&RemoteConnection> for RemoteConnectionInfo {
+    fn from(connection: &RemoteConnection) -> Self {
+        RemoteConnectionInfo {
+            id: connection.id,
+            account_id: connection.account_id.clone(),
+            account_name: connection.account_name.clone(),
+            label: connection.label.clone(),
+            permissions: connection.permissions.clone(),
+            source: connection.source,
+            auth_scheme: connection.auth.scheme().to_string(),
+            needs_reauth: connection.needs_reauth(),
+            permissions_unknown: connection.permissions.write.is_none(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimitBudget {
     pub account_id: String,
-    pub api_token: String,
+    pub label: String,
+    pub limit: usize,
+    pub used: usize,
+    pub remaining: usize,
+    pub window_seconds: u64,
+    pub resets_in_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VaultStatus {
+    pub configured: bool,
+    pub unlocked: bool,
+    /// Saved tokens are still in plain text, so a master password has to be set.
+    pub required: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -56,8 +197,29 @@ pub struct KVNamespace {
     pub folder_id: Option<i64>,
     #[serde(default)]
     pub count: Option<usize>,
+    #[serde(default)]
+    pub synced_at: Option<i64>,
 }
 
 pub fn default_namespace_type() -> String {
     "local".to_string()
-}
\ No newline at end of file
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountError {
+    pub account_id: String,
+    pub label: String,
+    pub error: ApiError,
+    pub needs_reauth: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoteNamespaces {
+    pub namespaces: Vec<KVNamespace>,
+    pub errors: Vec<AccountError>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExternalConnections {
+    pub connections: Vec<RemoteConnectionInfo>,
+    pub errors: Vec<AccountError>,
+}
@@ -1,2 +1,10 @@
 pub mod kv;
-pub mod cloudflare;
\ No newline at end of file
+pub mod cloudflare;
+pub mod value;
+pub mod cache;
+pub mod error;
+pub mod job;
+pub mod transfer;
+pub mod diff;
+pub mod sync;
+pub mod search;
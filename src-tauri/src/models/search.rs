@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::transfer::NamespaceRef;
+
+#[derive(Debug, Clone)]
+pub struct SearchDocument {
+    pub key: String,
+    pub revision: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SearchOptions {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Defaults to true.
+    #[serde(default)]
+    pub refresh: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchHit {
+    pub namespace: NamespaceRef,
+    pub key: String,
+    pub snippet: Vec<SnippetPart>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchResults {
+    pub hits: Vec<SearchHit>,
+    pub reindexed: usize,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::transfer::NamespaceRef;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPolicy {
+    PreferLeft,
+    PreferRight,
+    /// Keeps the side with the later modification time. Workers KV does not
+    /// report one, so conflicts involving a remote side are left to the user.
+    NewestWins,
+    Ask,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    CopyToRight,
+    CopyToLeft,
+    DeleteRight,
+    DeleteLeft,
+    Conflict,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncChange {
+    pub key: String,
+    pub action: SyncAction,
+    /// Both sides changed since the baseline, whether or not the policy resolved it.
+    pub conflict: bool,
+    pub left_fingerprint: Option<String>,
+    pub right_fingerprint: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncPlan {
+    pub left: NamespaceRef,
+    pub right: NamespaceRef,
+    pub policy: SyncPolicy,
+    pub changes: Vec<SyncChange>,
+    pub in_sync: usize,
+    pub has_baseline: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SyncResult {
+    pub copied_to_left: usize,
+    pub copied_to_right: usize,
+    pub deleted_left: usize,
+    pub deleted_right: usize,
+    pub unresolved: Vec<String>,
+    /// Keys that changed again after the plan was made and were left alone.
+    pub stale: Vec<String>,
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::kv::KVEntry;
+use crate::models::value::{fingerprint, KVValue};
+
+/// Cloudflare rejects expirations less than 60 seconds in the future.
+pub const MIN_EXPIRATION_SECONDS: i64 = 60;
+pub const MAX_KEY_BYTES: usize = 512;
+pub const MAX_VALUE_BYTES: usize = 25 * 1024 * 1024;
+pub const MAX_METADATA_BYTES: usize = 1024;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KVRecord {
+    pub key: String,
+    pub value: KVValue,
+    pub expiration: Option<i64>,
+    pub metadata: Option<Value>,
+    /// Last modification time where the backend exposes one. Workers KV does
+    /// not, so this is only set for local entries.
+    #[serde(default)]
+    pub modified_at: Option<i64>,
+}
+
+impl KVRecord {
+    pub fn fingerprint(&self) -> String {
+        let base = fingerprint(Some(&self.value), self.metadata.as_ref());
+        match self.expiration {
+            Some(expiration) => format!("{}:{}", base, expiration),
+            None => base,
+        }
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiration.is_some_and(|expiration| expiration < now + MIN_EXPIRATION_SECONDS)
+    }
+
+    /// Checks the record against the Workers KV limits so problems are caught
+    /// before anything is written.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.key.is_empty() || self.key == "." || self.key == ".." {
+            return Err(format!("\"{}\" is not a valid key name", self.key));
+        }
+        if self.key.len() > MAX_KEY_BYTES {
+            return Err(format!("Key name is {} bytes; keys are limited to {} bytes", self.key.len(), MAX_KEY_BYTES));
+        }
+        if self.value.bytes.len() > MAX_VALUE_BYTES {
+            return Err(format!("Value is {} bytes; values are limited to 25 MiB", self.value.bytes.len()));
+        }
+        if let Some(metadata) = &self.metadata {
+            let size = metadata.to_string().len();
+            if size > MAX_METADATA_BYTES {
+                return Err(format!("Metadata is {} bytes; metadata is limited to {} bytes", size, MAX_METADATA_BYTES));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NamespaceRef {
+    Local { folder_id: i64, namespace_id: String },
+    Remote { account_id: String, namespace_id: String },
+}
+
+impl NamespaceRef {
+    pub fn descriptor(&self) -> String {
+        match self {
+            NamespaceRef::Local { folder_id, namespace_id } => format!("local:{}:{}", folder_id, namespace_id),
+            NamespaceRef::Remote { account_id, namespace_id } => format!("remote:{}:{}", account_id, namespace_id),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyInfo {
+    pub key: String,
+    pub expiration: Option<i64>,
+    pub metadata: Option<Value>,
+}
+
+impl From<KVEntry> for KeyInfo {
+    fn from(entry: KVEntry) -> Self {
+        KeyInfo {
+            metadata: entry.metadata.and_then(|m| serde_json::from_str(&m).ok()),
+            key: entry.key,
+            expiration: entry.expiration,
+        }
+    }
+}
+
+impl From<&KVRecord> for KeyInfo {
+    fn from(record: &KVRecord) -> Self {
+        KeyInfo {
+            key: record.key.clone(),
+            expiration: record.expiration,
+            metadata: record.metadata.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteMode {
+    Overwrite,
+    SkipExisting,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TransferOptions {
+    pub mode: WriteMode,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteAction {
+    Create,
+    Overwrite,
+    Skip,
+    Expired,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlannedWrite {
+    pub key: String,
+    pub action: WriteAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransferSummary {
+    pub total: usize,
+    pub written: usize,
+    pub skipped: usize,
+    pub expired: usize,
+    #[serde(default)]
+    pub deleted: usize,
+    pub dry_run: bool,
+    pub plan: Vec<PlannedWrite>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskStrategy {
+    /// Drops the field. Array elements are redacted instead so indices stay stable.
+    Remove,
+    Redact,
+    /// Replaces the field with its SHA-256 digest, so masked ids can still be joined.
+    Hash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaskRule {
+    /// Dot-separated path into the JSON value, e.g. `user.email` or `items.*.token`.
+    pub field: String,
+    pub strategy: MaskStrategy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PullOptions {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub masks: Vec<MaskRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloneTarget {
+    pub account_id: String,
+    /// Existing namespace to write into. A new namespace called `title` is
+    /// created when this is not set.
+    #[serde(default)]
+    pub namespace_id: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloneCheckpoint {
+    pub source: NamespaceRef,
+    pub target: NamespaceRef,
+    /// Title of a target namespace the clone created, so a resumed run reuses it.
+    #[serde(default)]
+    pub target_title: Option<String>,
+    pub last_key: String,
+    pub copied: usize,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CloneResult {
+    pub namespace_id: String,
+    pub copied: usize,
+    pub expired: usize,
+    pub resumed_from: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportFilter {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub keys: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportSummary {
+    pub path: String,
+    pub exported: usize,
+    pub expired: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    BulkJson,
+    Ndjson,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CsvColumns {
+    pub key: String,
+    pub value: String,
+    #[serde(default)]
+    pub metadata: Option<String>,
+    #[serde(default)]
+    pub expiration: Option<String>,
+}
+
+impl Default for CsvColumns {
+    fn default() -> Self {
+        CsvColumns {
+            key: "key".to_string(),
+            value: "value".to_string(),
+            metadata: None,
+            expiration: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    #[serde(default)]
+    pub csv_columns: Option<CsvColumns>,
+    pub mode: WriteMode,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectoryImportOptions {
+    pub mode: WriteMode,
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Deletes keys in the namespace that have no file in the tree.
+    #[serde(default)]
+    pub prune: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportIssue {
+    /// Line number for NDJSON and CSV, position in the array for bulk JSON.
+    /// Zero for directory imports, which set `file` instead.
+    pub line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImportSummary {
+    #[serde(flatten)]
+    pub transfer: TransferSummary,
+    pub issues: Vec<ImportIssue>,
+}
+
+pub const DIRECTORY_MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ManifestEntry {
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// Keyed by file path relative to the `keys` directory, sorted so diffs stay small.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DirectoryManifest {
+    pub version: u32,
+    pub keys: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct NdjsonExportOptions {
+    #[serde(default)]
+    pub filter: ExportFilter,
+    #[serde(default)]
+    pub gzip: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NdjsonCheckpoint {
+    pub namespace: NamespaceRef,
+    pub options: NdjsonExportOptions,
+    pub cursor: Option<String>,
+    /// Bytes of the partial file up to the last complete page.
+    pub offset: u64,
+    pub exported: usize,
+    pub expired: usize,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NdjsonExportSummary {
+    pub path: String,
+    pub namespace: NamespaceRef,
+    pub exported: usize,
+    pub expired: usize,
+    pub gzip: bool,
+    pub resumed: bool,
+    pub completed_at: i64,
+}
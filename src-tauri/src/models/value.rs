@@ -0,0 +1,156 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::models::error::ApiError;
+
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\x00asm", "application/wasm"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueKind {
+    Json,
+    Text,
+    Binary,
+    Opaque,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KVValue {
+    pub kind: ValueKind,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(rename = "base64", serialize_with = "serialize_bytes", deserialize_with = "deserialize_bytes")]
+    pub bytes: Vec<u8>,
+    #[serde(default)]
+    pub size: usize,
+}
+
+impl KVValue {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let (kind, mime_type) = detect_kind(&bytes);
+
+        KVValue {
+            kind,
+            mime_type: mime_type.map(|m| m.to_string()),
+            size: bytes.len(),
+            bytes,
+        }
+    }
+
+    pub fn content_type(&self) -> &str {
+        match self.kind {
+            ValueKind::Json => "application/json",
+            ValueKind::Text => "text/plain; charset=utf-8",
+            ValueKind::Binary => self.mime_type.as_deref().unwrap_or("application/octet-stream"),
+            ValueKind::Opaque => "application/octet-stream",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RemoteValueSnapshot {
+    pub value: KVValue,
+    pub metadata: Option<Value>,
+    pub fingerprint: String,
+}
+
+impl RemoteValueSnapshot {
+    pub fn new(value: KVValue, metadata: Option<Value>) -> Self {
+        RemoteValueSnapshot {
+            fingerprint: fingerprint(Some(&value), metadata.as_ref()),
+            value,
+            metadata,
+        }
+    }
+}
+
+pub fn fingerprint(value: Option<&KVValue>, metadata: Option<&Value>) -> String {
+    let mut hasher = Sha256::new();
+
+    match value {
+        Some(value) => {
+            hasher.update(b"value:");
+            hasher.update((value.bytes.len() as u64).to_le_bytes());
+            hasher.update(&value.bytes);
+        }
+        None => hasher.update(b"absent"),
+    }
+
+    if let Some(metadata) = metadata {
+        hasher.update(b"metadata:");
+        hasher.update(metadata.to_string().as_bytes());
+    }
+
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkValueResult {
+    pub value: Option<KVValue>,
+    pub error: Option<ApiError>,
+}
+
+impl BulkValueResult {
+    pub fn value(value: KVValue) -> Self {
+        BulkValueResult { value: Some(value), error: None }
+    }
+
+    pub fn error(error: ApiError) -> Self {
+        BulkValueResult { value: None, error: Some(error) }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkMetadataResult {
+    pub metadata: Option<Value>,
+    pub error: Option<ApiError>,
+}
+
+impl BulkMetadataResult {
+    pub fn metadata(metadata: Option<Value>) -> Self {
+        BulkMetadataResult { metadata, error: None }
+    }
+
+    pub fn error(error: ApiError) -> Self {
+        BulkMetadataResult { metadata: None, error: Some(error) }
+    }
+}
+
+fn detect_kind(bytes: &[u8]) -> (ValueKind, Option<&'static str>) {
+    if let Some((_, mime_type)) = MAGIC_NUMBERS.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return (ValueKind::Binary, Some(mime_type));
+    }
+
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return (ValueKind::Binary, Some("image/webp"));
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) if serde_json::from_str::<Value>(text).is_ok() => (ValueKind::Json, Some("application/json")),
+        Ok(_) => (ValueKind::Text, Some("text/plain")),
+        Err(_) => (ValueKind::Opaque, None),
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(bytes))
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
@@ -1,17 +1,125 @@
-use rusqlite::{Connection, params, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
-use crate::models::kv::RemoteConnection;
+use crate::models::cache::{CacheEntry, CacheKey, CacheKind};
+use crate::models::kv::{ApiAuth, CredentialSource, RemoteConnection, TokenPermissions};
+use crate::models::search::SearchDocument;
+use crate::models::transfer::{CloneCheckpoint, NamespaceRef};
+use crate::search::{HIGHLIGHT_END, HIGHLIGHT_START, SNIPPET_TOKENS};
+use crate::vault::{self, TokenCipher};
 
-const DB_VERSION: i32 = 1;
+const DB_VERSION: i32 = 9;
+const VAULT_SALT_SETTING: &str = "vault_salt";
+const VAULT_CHECK_SETTING: &str = "vault_check";
+const VAULT_CHECK_PLAINTEXT: &str = "kv-explorer-vault";
+const MAX_CACHED_VALUES: i64 = 500;
+
+/// Account id, namespace id, key, sync time and payload of a cached value.
+type CachedValueRow = (String, String, String, i64, String);
+/// Account id, namespace id and key of a cached value.
+type CachedKeyRow = (String, String, String);
+
+#[derive(Debug)]
+pub enum DbError {
+    Sqlite(rusqlite::Error),
+    Vault(String),
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Sqlite(e) => write!(f, "{}", e),
+            DbError::Vault(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(error: rusqlite::Error) -> Self {
+        DbError::Sqlite(error)
+    }
+}
+
+pub type Result<T, E = DbError> = std::result::Result<T, E>;
+
+fn auth_columns(auth: &ApiAuth) -> (&str, Option<&str>) {
+    match auth {
+        ApiAuth::Bearer { token } => (token, None),
+        ApiAuth::GlobalKey { email, key } => (key, Some(email)),
+    }
+}
+
+fn namespace_column(namespace: &NamespaceRef) -> String {
+    serde_json::to_string(namespace).unwrap_or_default()
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(1))?.collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(columns.iter().any(|c| c == column))
+}
+
+fn vault_error(message: impl Into<String>) -> DbError {
+    DbError::Vault(message.into())
+}
+
+fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?",
+        params![key],
+        |row| row.get(0),
+    ).optional()?)
+}
+
+fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)",
+        params![key, value],
+    )?;
+
+    Ok(())
+}
+
+fn reencrypt_secrets(conn: &Connection, current: Option<&TokenCipher>, next: &TokenCipher) -> Result<()> {
+    let mut stmt = conn.prepare("SELECT id, api_token FROM remote_connections")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    for (id, stored) in rows {
+        let secret = match (vault::is_encrypted(&stored), current) {
+            (true, Some(cipher)) => cipher.decrypt(&stored).map_err(vault_error)?,
+            (true, None) => return Err(vault_error("Stored secret is encrypted with an unknown key")),
+            (false, _) => stored,
+        };
+
+        if secret.is_empty() {
+            continue;
+        }
+
+        conn.execute(
+            "UPDATE remote_connections SET api_token = ? WHERE id = ?",
+            params![next.encrypt(&secret).map_err(vault_error)?, id],
+        )?;
+    }
+
+    Ok(())
+}
 
 pub struct Database {
     conn: Connection,
+    cipher: Option<TokenCipher>,
 }
 
 impl Database {
     pub fn new(app_data_dir: &PathBuf) -> Result<Self> {
       if let Err(_e) = std::fs::create_dir_all(app_data_dir) {
-          return Err(rusqlite::Error::ExecuteReturnedResults);
+          return Err(rusqlite::Error::ExecuteReturnedResults.into());
       }
 
       let db_path = app_data_dir.join("kv_explorer.db");
@@ -19,7 +127,7 @@ impl Database {
 
       Self::initialize_database(&conn)?;
 
-      Ok(Self { conn })
+      Ok(Self { conn, cipher: None })
     }
 
     fn initialize_database(conn: &Connection) -> Result<()> {
@@ -45,18 +153,119 @@ impl Database {
             "CREATE TABLE IF NOT EXISTS remote_connections (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 account_id TEXT NOT NULL UNIQUE,
+                account_name TEXT,
+                label TEXT NOT NULL DEFAULT '',
                 api_token TEXT NOT NULL,
+                auth_email TEXT,
+                permissions TEXT NOT NULL DEFAULT '{}',
+                source TEXT NOT NULL DEFAULT 'manual',
+                failure_count INTEGER NOT NULL DEFAULT 0,
                 last_used INTEGER NOT NULL
             )",
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS remote_cache (
+                account_id TEXT NOT NULL,
+                namespace_id TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                cache_key TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                synced_at INTEGER NOT NULL,
+                expires_at INTEGER NOT NULL,
+                PRIMARY KEY (account_id, namespace_id, kind, cache_key)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS sync_baselines (
+                pair_id TEXT NOT NULL,
+                key TEXT NOT NULL,
+                fingerprint TEXT NOT NULL,
+                synced_at INTEGER NOT NULL,
+                PRIMARY KEY (pair_id, key)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS clone_checkpoints (
+                source TEXT NOT NULL,
+                target TEXT NOT NULL,
+                target_title TEXT,
+                last_key TEXT NOT NULL,
+                copied INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                PRIMARY KEY (source, target)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_documents (
+                id INTEGER PRIMARY KEY,
+                source TEXT NOT NULL,
+                key TEXT NOT NULL,
+                revision TEXT NOT NULL,
+                UNIQUE (source, key)
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(key, content, tokenize = 'unicode61')",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS search_marks (
+                name TEXT PRIMARY KEY,
+                indexed_through INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         let mut stmt = conn.prepare("SELECT value FROM app_settings WHERE key = 'schema_version'")?;
-        let version: Result<String> = stmt.query_row([], |row| row.get(0));
+        let version: rusqlite::Result<String> = stmt.query_row([], |row| row.get(0));
 
         match version {
             Ok(v) => {
                 let current_version: i32 = v.parse().unwrap_or(0);
+                if current_version < 2 {
+                    conn.execute(
+                        "ALTER TABLE remote_connections ADD COLUMN label TEXT NOT NULL DEFAULT ''",
+                        [],
+                    )?;
+                }
+                if current_version < 3 {
+                    conn.execute(
+                        "ALTER TABLE remote_connections ADD COLUMN permissions TEXT NOT NULL DEFAULT '{}'",
+                        [],
+                    )?;
+                }
+                if current_version < 4 {
+                    conn.execute("ALTER TABLE remote_connections ADD COLUMN account_name TEXT", [])?;
+                }
+                if current_version < 5 {
+                    conn.execute(
+                        "ALTER TABLE remote_connections ADD COLUMN source TEXT NOT NULL DEFAULT 'manual'",
+                        [],
+                    )?;
+                }
+                if current_version < 6 {
+                    conn.execute("ALTER TABLE remote_connections ADD COLUMN auth_email TEXT", [])?;
+                }
+                if current_version < 7 {
+                    conn.execute(
+                        "ALTER TABLE remote_connections ADD COLUMN failure_count INTEGER NOT NULL DEFAULT 0",
+                        [],
+                    )?;
+                }
+                if current_version < 9 && !has_column(conn, "clone_checkpoints", "target_title")? {
+                    conn.execute("ALTER TABLE clone_checkpoints ADD COLUMN target_title TEXT", [])?;
+                }
                 if current_version < DB_VERSION {
                     conn.execute(
                         "UPDATE app_settings SET value = ? WHERE key = 'schema_version'",
@@ -127,37 +336,225 @@ impl Database {
         Ok(())
     }
 
-    pub fn save_remote_connection(&self, account_id: &str, api_token: &str) -> Result<i64> {
+    pub fn is_vault_configured(&self) -> Result<bool> {
+        Ok(get_setting(&self.conn, VAULT_SALT_SETTING)?.is_some())
+    }
+
+    pub fn has_unencrypted_secrets(&self) -> Result<bool> {
+        let mut stmt = self.conn.prepare("SELECT api_token FROM remote_connections WHERE api_token != ''")?;
+        let tokens = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tokens.iter().any(|token| !vault::is_encrypted(token)))
+    }
+
+    pub fn is_vault_unlocked(&self) -> bool {
+        self.cipher.is_some()
+    }
+
+    pub fn create_vault(&mut self, password: &str) -> Result<()> {
+        if self.is_vault_configured()? {
+            return Err(vault_error("A master password has already been set"));
+        }
+
+        let salt = TokenCipher::generate_salt();
+        let cipher = TokenCipher::derive(password, &salt).map_err(vault_error)?;
+
+        let tx = self.conn.transaction()?;
+        set_setting(&tx, VAULT_SALT_SETTING, &STANDARD.encode(&salt))?;
+        set_setting(&tx, VAULT_CHECK_SETTING, &cipher.encrypt(VAULT_CHECK_PLAINTEXT).map_err(vault_error)?)?;
+        reencrypt_secrets(&tx, None, &cipher)?;
+        tx.commit()?;
+
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    pub fn unlock_vault(&mut self, password: &str) -> Result<()> {
+        let salt = get_setting(&self.conn, VAULT_SALT_SETTING)?
+            .ok_or_else(|| vault_error("No master password has been set"))?;
+        let salt = STANDARD.decode(salt).map_err(|_| vault_error("Vault salt is corrupted"))?;
+        let check = get_setting(&self.conn, VAULT_CHECK_SETTING)?
+            .ok_or_else(|| vault_error("Vault check value is missing"))?;
+
+        let cipher = TokenCipher::derive(password, &salt).map_err(vault_error)?;
+        match cipher.decrypt(&check) {
+            Ok(plaintext) if plaintext == VAULT_CHECK_PLAINTEXT => {}
+            _ => return Err(vault_error("Incorrect master password")),
+        }
+
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    pub fn lock_vault(&mut self) {
+        self.cipher = None;
+    }
+
+    pub fn change_master_password(&mut self, current_password: &str, new_password: &str) -> Result<()> {
+        self.unlock_vault(current_password)?;
+        let current = self.cipher.clone();
+
+        let salt = TokenCipher::generate_salt();
+        let cipher = TokenCipher::derive(new_password, &salt).map_err(vault_error)?;
+
+        let tx = self.conn.transaction()?;
+        set_setting(&tx, VAULT_SALT_SETTING, &STANDARD.encode(&salt))?;
+        set_setting(&tx, VAULT_CHECK_SETTING, &cipher.encrypt(VAULT_CHECK_PLAINTEXT).map_err(vault_error)?)?;
+        reencrypt_secrets(&tx, current.as_ref(), &cipher)?;
+        tx.commit()?;
+
+        self.cipher = Some(cipher);
+        Ok(())
+    }
+
+    fn seal_secret(&self, secret: &str) -> Result<String> {
+        if secret.is_empty() {
+            return Ok(String::new());
+        }
+
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(secret).map_err(vault_error),
+            None if self.is_vault_configured()? => Err(vault_error("Unlock the app with your master password first")),
+            None => Err(vault_error("Set a master password before saving credentials")),
+        }
+    }
+
+    fn open_secret(&self, stored: String) -> Result<String> {
+        if !vault::is_encrypted(&stored) {
+            return Ok(stored);
+        }
+
+        match &self.cipher {
+            Some(cipher) => cipher.decrypt(&stored).map_err(vault_error),
+            None => Err(vault_error("Unlock the app with your master password first")),
+        }
+    }
+
+    pub fn save_remote_connection(&self, connection: &RemoteConnection) -> Result<i64> {
         let timestamp = chrono::Utc::now().timestamp();
+        let permissions = serde_json::to_string(&connection.permissions).unwrap_or_else(|_| "{}".to_string());
+        let (api_token, auth_email) = auth_columns(&connection.auth);
+        let api_token = self.seal_secret(api_token)?;
 
         self.conn.execute(
-            "INSERT OR REPLACE INTO remote_connections (account_id, api_token, last_used) VALUES (?, ?, ?)",
-            params![account_id, api_token, timestamp],
+            "INSERT INTO remote_connections
+                (account_id, account_name, label, api_token, auth_email, permissions, source, last_used)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(account_id) DO UPDATE SET
+                account_name = COALESCE(excluded.account_name, account_name),
+                label = excluded.label,
+                api_token = excluded.api_token,
+                auth_email = excluded.auth_email,
+                permissions = excluded.permissions,
+                source = excluded.source,
+                failure_count = 0,
+                last_used = excluded.last_used",
+            params![
+                connection.account_id,
+                connection.account_name,
+                connection.label,
+                api_token,
+                auth_email,
+                permissions,
+                connection.source.as_str(),
+                timestamp
+            ],
         )?;
 
-        Ok(self.conn.last_insert_rowid())
+        Ok(self.conn.query_row(
+            "SELECT id FROM remote_connections WHERE account_id = ?",
+            params![connection.account_id],
+            |row| row.get(0),
+        )?)
     }
 
     pub fn get_remote_connections(&self) -> Result<Vec<RemoteConnection>> {
         let mut stmt = self.conn.prepare(
-            "SELECT account_id, api_token FROM remote_connections ORDER BY last_used DESC"
+            "SELECT id, account_id, account_name, label, api_token, auth_email, permissions, source, failure_count
+             FROM remote_connections ORDER BY last_used DESC"
         )?;
 
         let connection_iter = stmt.query_map([], |row| {
-            Ok(RemoteConnection {
-                account_id: row.get::<_, String>(0)?,
-                api_token: row.get::<_, String>(1)?,
-            })
+            let account_id = row.get::<_, String>(1)?;
+            let account_name = row.get::<_, Option<String>>(2)?;
+            let label = row.get::<_, String>(3)?;
+            let permissions = row.get::<_, String>(6)?;
+
+            let connection = RemoteConnection {
+                id: row.get::<_, i64>(0)?,
+                label: if label.is_empty() { account_id.clone() } else { label },
+                account_id,
+                account_name,
+                auth: ApiAuth::Bearer { token: String::new() },
+                permissions: serde_json::from_str(&permissions).unwrap_or_default(),
+                source: CredentialSource::from_str(&row.get::<_, String>(7)?),
+                failure_count: row.get::<_, u32>(8)?,
+            };
+            Ok((connection, row.get::<_, String>(4)?, row.get::<_, Option<String>>(5)?))
         })?;
 
         let mut connections = Vec::new();
-        for connection in connection_iter {
-            connections.push(connection?);
+        for row in connection_iter {
+            let (mut connection, api_token, auth_email) = row?;
+            connection.auth = ApiAuth::new(self.open_secret(api_token)?, auth_email);
+            connections.push(connection);
         }
 
         Ok(connections)
     }
 
+    pub fn rename_connection(&self, id: i64, label: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE remote_connections SET label = ? WHERE id = ?",
+            params![label, id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_connection_auth(&self, id: i64, auth: &ApiAuth, permissions: &TokenPermissions) -> Result<()> {
+        let permissions = serde_json::to_string(permissions).unwrap_or_else(|_| "{}".to_string());
+        let (api_token, auth_email) = auth_columns(auth);
+        let api_token = self.seal_secret(api_token)?;
+
+        self.conn.execute(
+            "UPDATE remote_connections SET api_token = ?, auth_email = ?, permissions = ?, source = 'manual', failure_count = 0 WHERE id = ?",
+            params![api_token, auth_email, permissions, id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn record_connection_failure(&self, id: i64) -> Result<u32> {
+        self.conn.execute(
+            "UPDATE remote_connections SET failure_count = failure_count + 1 WHERE id = ?",
+            params![id],
+        )?;
+
+        Ok(self.conn.query_row(
+            "SELECT failure_count FROM remote_connections WHERE id = ?",
+            params![id],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn reset_connection_failures(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE remote_connections SET failure_count = 0 WHERE id = ?",
+            params![id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_connection(&self, id: i64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM remote_connections WHERE id = ?",
+            params![id],
+        )?;
+
+        Ok(())
+    }
+
     pub fn update_connection_timestamp(&self, account_id: &str) -> Result<()> {
         let timestamp = chrono::Utc::now().timestamp();
 
@@ -173,4 +570,477 @@ impl Database {
         self.conn.execute("DELETE FROM remote_connections", [])?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    pub fn get_cache_entry(&self, key: &CacheKey) -> Result<Option<CacheEntry>> {
+        Ok(self.conn.query_row(
+            "SELECT payload, synced_at, expires_at FROM remote_cache
+             WHERE account_id = ? AND namespace_id = ? AND kind = ? AND cache_key = ?",
+            params![key.account_id, key.namespace_id, key.kind.as_str(), key.key],
+            |row| Ok(CacheEntry {
+                payload: row.get(0)?,
+                synced_at: row.get(1)?,
+                expires_at: row.get(2)?,
+            }),
+        ).optional()?)
+    }
+
+    pub fn put_cache_entry(&self, key: &CacheKey, payload: &str) -> Result<i64> {
+        let timestamp = chrono::Utc::now().timestamp();
+
+        self.conn.execute(
+            "INSERT OR REPLACE INTO remote_cache
+                (account_id, namespace_id, kind, cache_key, payload, synced_at, expires_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            params![
+                key.account_id,
+                key.namespace_id,
+                key.kind.as_str(),
+                key.key,
+                payload,
+                timestamp,
+                timestamp + key.kind.ttl_seconds()
+            ],
+        )?;
+
+        if key.kind == CacheKind::Value {
+            self.conn.execute(
+                "DELETE FROM remote_cache WHERE kind = 'value' AND rowid NOT IN (
+                    SELECT rowid FROM remote_cache WHERE kind = 'value' ORDER BY synced_at DESC LIMIT ?
+                )",
+                params![MAX_CACHED_VALUES],
+            )?;
+        }
+
+        Ok(timestamp)
+    }
+
+    pub fn remove_cache_entry(&self, key: &CacheKey) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM remote_cache WHERE account_id = ? AND namespace_id = ? AND kind = ? AND cache_key = ?",
+            params![key.account_id, key.namespace_id, key.kind.as_str(), key.key],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn invalidate_cached_keys(&self, account_id: &str, namespace_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM remote_cache WHERE account_id = ? AND namespace_id = ? AND kind = 'keys'",
+            params![account_id, namespace_id],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn clear_cache(&self, account_id: Option<&str>) -> Result<()> {
+        match account_id {
+            Some(account_id) => self.conn.execute(
+                "DELETE FROM remote_cache WHERE account_id = ?",
+                params![account_id],
+            )?,
+            None => self.conn.execute("DELETE FROM remote_cache", [])?,
+        };
+
+        Ok(())
+    }
+
+    pub fn cache_summary(&self) -> Result<(i64, Option<i64>)> {
+        Ok(self.conn.query_row(
+            "SELECT COUNT(*), MAX(synced_at) FROM remote_cache",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?)
+    }
+
+    pub fn get_sync_baseline(&self, pair_id: &str) -> Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT key, fingerprint FROM sync_baselines WHERE pair_id = ?")?;
+        let rows = stmt.query_map(params![pair_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn save_sync_baseline(&self, pair_id: &str, entries: &[(String, String)]) -> Result<()> {
+        let timestamp = chrono::Utc::now().timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO sync_baselines (pair_id, key, fingerprint, synced_at) VALUES (?, ?, ?, ?)"
+            )?;
+            for (key, fingerprint) in entries {
+                stmt.execute(params![pair_id, key, fingerprint, timestamp])?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    pub fn remove_sync_baseline(&self, pair_id: &str, keys: &[String]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+
+        {
+            let mut stmt = tx.prepare("DELETE FROM sync_baselines WHERE pair_id = ? AND key = ?")?;
+            for key in keys {
+                stmt.execute(params![pair_id, key])?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    pub fn get_clone_checkpoint(&self, source: &NamespaceRef, target: &NamespaceRef) -> Result<Option<CloneCheckpoint>> {
+        Ok(self.conn.query_row(
+            "SELECT target_title, last_key, copied, updated_at FROM clone_checkpoints WHERE source = ? AND target = ?",
+            params![namespace_column(source), namespace_column(target)],
+            |row| Ok(CloneCheckpoint {
+                source: source.clone(),
+                target: target.clone(),
+                target_title: row.get(0)?,
+                last_key: row.get(1)?,
+                copied: row.get(2)?,
+                updated_at: row.get(3)?,
+            }),
+        ).optional()?)
+    }
+
+    pub fn list_clone_checkpoints(&self) -> Result<Vec<CloneCheckpoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT source, target, target_title, last_key, copied, updated_at FROM clone_checkpoints ORDER BY updated_at DESC"
+        )?;
+
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, usize>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?;
+
+        let mut checkpoints = Vec::new();
+        for row in rows {
+            let (source, target, target_title, last_key, copied, updated_at) = row?;
+            if let (Ok(source), Ok(target)) = (serde_json::from_str(&source), serde_json::from_str(&target)) {
+                checkpoints.push(CloneCheckpoint { source, target, target_title, last_key, copied, updated_at });
+            }
+        }
+
+        Ok(checkpoints)
+    }
+
+    /// Finds the checkpoint of a clone from `source` into a namespace titled
+    /// `title` that the clone created in `account_id`.
+    pub fn find_clone_checkpoint_by_title(&self, source: &NamespaceRef, account_id: &str, title: &str) -> Result<Option<CloneCheckpoint>> {
+        Ok(self.list_clone_checkpoints()?.into_iter().find(|c| {
+            c.source.descriptor() == source.descriptor()
+                && c.target_title.as_deref() == Some(title)
+                && matches!(&c.target, NamespaceRef::Remote { account_id: target_account, .. } if target_account == account_id)
+        }))
+    }
+
+    pub fn save_clone_checkpoint(&self, checkpoint: &CloneCheckpoint) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO clone_checkpoints (source, target, target_title, last_key, copied, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+            params![
+                namespace_column(&checkpoint.source),
+                namespace_column(&checkpoint.target),
+                checkpoint.target_title,
+                checkpoint.last_key,
+                checkpoint.copied,
+                checkpoint.updated_at
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn remove_clone_checkpoint(&self, source: &NamespaceRef, target: &NamespaceRef) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM clone_checkpoints WHERE source = ? AND target = ?",
+            params![namespace_column(source), namespace_column(target)],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_search_revisions(&self, source: &NamespaceRef) -> Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare("SELECT key, revision FROM search_documents WHERE source = ?")?;
+        let rows = stmt.query_map(params![namespace_column(source)], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn index_search_documents(&self, source: &NamespaceRef, documents: &[SearchDocument]) -> Result<()> {
+        let source = namespace_column(source);
+        let tx = self.conn.unchecked_transaction()?;
+
+        for document in documents {
+            let existing: Option<i64> = tx.query_row(
+                "SELECT id FROM search_documents WHERE source = ? AND key = ?",
+                params![source, document.key],
+                |row| row.get(0),
+            ).optional()?;
+
+            let id = match existing {
+                Some(id) => {
+                    tx.execute("DELETE FROM search_index WHERE rowid = ?", params![id])?;
+                    tx.execute("UPDATE search_documents SET revision = ? WHERE id = ?", params![document.revision, id])?;
+                    id
+                }
+                None => {
+                    tx.execute(
+                        "INSERT INTO search_documents (source, key, revision) VALUES (?, ?, ?)",
+                        params![source, document.key, document.revision],
+                    )?;
+                    tx.last_insert_rowid()
+                }
+            };
+
+            tx.execute(
+                "INSERT INTO search_index (rowid, key, content) VALUES (?, ?, ?)",
+                params![id, document.key, document.content],
+            )?;
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    pub fn remove_search_documents(&self, source: &NamespaceRef, keys: &[String]) -> Result<()> {
+        let source = namespace_column(source);
+        let tx = self.conn.unchecked_transaction()?;
+
+        {
+            let mut remove_index = tx.prepare(
+                "DELETE FROM search_index WHERE rowid IN (SELECT id FROM search_documents WHERE source = ? AND key = ?)"
+            )?;
+            let mut remove_document = tx.prepare("DELETE FROM search_documents WHERE source = ? AND key = ?")?;
+            for key in keys {
+                remove_index.execute(params![source, key])?;
+                remove_document.execute(params![source, key])?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    pub fn list_search_sources(&self) -> Result<Vec<NamespaceRef>> {
+        let mut stmt = self.conn.prepare("SELECT DISTINCT source FROM search_documents")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        Ok(rows.flatten().filter_map(|source| serde_json::from_str(&source).ok()).collect())
+    }
+
+    pub fn remove_search_source(&self, source: &NamespaceRef) -> Result<()> {
+        let descriptor = source.descriptor();
+        let source = namespace_column(source);
+        let tx = self.conn.unchecked_transaction()?;
+
+        tx.execute(
+            "DELETE FROM search_index WHERE rowid IN (SELECT id FROM search_documents WHERE source = ?)",
+            params![source],
+        )?;
+        tx.execute("DELETE FROM search_documents WHERE source = ?", params![source])?;
+        tx.execute("DELETE FROM search_marks WHERE name = ?", params![descriptor])?;
+
+        Ok(tx.commit()?)
+    }
+
+    pub fn get_search_mark(&self, name: &str) -> Result<Option<i64>> {
+        Ok(self.conn.query_row(
+            "SELECT indexed_through FROM search_marks WHERE name = ?",
+            params![name],
+            |row| row.get(0),
+        ).optional()?)
+    }
+
+    pub fn set_search_mark(&self, name: &str, indexed_through: i64) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO search_marks (name, indexed_through) VALUES (?, ?)",
+            params![name, indexed_through],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_cached_value_keys(&self) -> Result<Vec<CachedKeyRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account_id, namespace_id, cache_key FROM remote_cache WHERE kind = 'value'"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    pub fn get_cached_values(&self, synced_since: i64) -> Result<Vec<CachedValueRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT account_id, namespace_id, cache_key, synced_at, payload FROM remote_cache
+             WHERE kind = 'value' AND synced_at >= ?"
+        )?;
+        let rows = stmt.query_map(
+            params![synced_since],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )?;
+
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Snippets mark matches with `HIGHLIGHT_START` and `HIGHLIGHT_END`.
+    pub fn search_documents(&self, query: &str, limit: usize) -> Result<Vec<(NamespaceRef, String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT d.source, d.key, snippet(search_index, -1, ?, ?, '…', ?)
+             FROM search_index JOIN search_documents d ON d.id = search_index.rowid
+             WHERE search_index MATCH ?
+             ORDER BY rank LIMIT ?"
+        )?;
+        let rows = stmt.query_map(
+            params![HIGHLIGHT_START, HIGHLIGHT_END, SNIPPET_TOKENS, query, limit as i64],
+            |row| Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        Ok(rows
+            .collect::<rusqlite::Result<Vec<_>>>()?
+            .into_iter()
+            .filter_map(|(source, key, snippet)| serde_json::from_str(&source).ok().map(|source| (source, key, snippet)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Database {
+        let conn = Connection::open_in_memory().unwrap();
+        Database::initialize_database(&conn).unwrap();
+        Database { conn, cipher: None }
+    }
+
+    fn remote(account_id: &str, namespace_id: &str) -> NamespaceRef {
+        NamespaceRef::Remote { account_id: account_id.to_string(), namespace_id: namespace_id.to_string() }
+    }
+
+    fn checkpoint(target: NamespaceRef, target_title: Option<&str>, last_key: &str) -> CloneCheckpoint {
+        CloneCheckpoint {
+            source: remote("source", "templates"),
+            target,
+            target_title: target_title.map(str::to_string),
+            last_key: last_key.to_string(),
+            copied: 3,
+            updated_at: 1,
+        }
+    }
+
+    fn stored_token(db: &Database) -> String {
+        db.conn.query_row("SELECT api_token FROM remote_connections", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn saved_tokens_are_migrated_when_the_vault_is_created() {
+        let mut db = test_db();
+        let connection = RemoteConnection {
+            id: 0,
+            account_id: "account".to_string(),
+            account_name: None,
+            label: "Account".to_string(),
+            auth: ApiAuth::Bearer { token: "secret-token".to_string() },
+            permissions: TokenPermissions::default(),
+            source: CredentialSource::Manual,
+            failure_count: 0,
+        };
+        assert!(matches!(db.save_remote_connection(&connection), Err(DbError::Vault(_))));
+
+        db.conn.execute(
+            "INSERT INTO remote_connections (account_id, label, api_token, last_used) VALUES ('account', 'Account', 'secret-token', 0)",
+            [],
+        ).unwrap();
+        assert!(db.has_unencrypted_secrets().unwrap());
+
+        db.create_vault("correct horse").unwrap();
+        assert!(vault::is_encrypted(&stored_token(&db)));
+        assert!(!db.has_unencrypted_secrets().unwrap());
+
+        db.lock_vault();
+        assert!(matches!(db.get_remote_connections(), Err(DbError::Vault(_))));
+        assert!(matches!(db.unlock_vault("wrong password"), Err(DbError::Vault(_))));
+
+        db.unlock_vault("correct horse").unwrap();
+        db.save_remote_connection(&connection).unwrap();
+        let connections = db.get_remote_connections().unwrap();
+        assert_eq!(connections[0].auth, ApiAuth::Bearer { token: "secret-token".to_string() });
+    }
+
+    #[test]
+    fn clone_checkpoint_round_trip() {
+        let db = test_db();
+        let saved = checkpoint(remote("target", "ns-1"), None, "key-3");
+        db.save_clone_checkpoint(&saved).unwrap();
+
+        let loaded = db.get_clone_checkpoint(&saved.source, &saved.target).unwrap().unwrap();
+        assert_eq!(loaded.last_key, "key-3");
+        assert_eq!(loaded.copied, 3);
+
+        db.remove_clone_checkpoint(&saved.source, &saved.target).unwrap();
+        assert!(db.get_clone_checkpoint(&saved.source, &saved.target).unwrap().is_none());
+    }
+
+    #[test]
+    fn finds_created_namespace_by_title() {
+        let db = test_db();
+        let source = remote("source", "templates");
+        db.save_clone_checkpoint(&checkpoint(remote("target", "ns-new"), Some("customer-config"), "")).unwrap();
+        db.save_clone_checkpoint(&checkpoint(remote("target", "ns-other"), None, "a")).unwrap();
+
+        let found = db.find_clone_checkpoint_by_title(&source, "target", "customer-config").unwrap().unwrap();
+        assert_eq!(found.target.descriptor(), remote("target", "ns-new").descriptor());
+
+        assert!(db.find_clone_checkpoint_by_title(&source, "elsewhere", "customer-config").unwrap().is_none());
+        assert!(db.find_clone_checkpoint_by_title(&source, "target", "other-title").unwrap().is_none());
+    }
+
+    #[test]
+    fn migrates_clone_checkpoints_without_title() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE app_settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+             INSERT INTO app_settings (key, value) VALUES ('schema_version', '8');
+             CREATE TABLE remote_connections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, account_id TEXT NOT NULL UNIQUE, account_name TEXT,
+                label TEXT NOT NULL DEFAULT '', api_token TEXT NOT NULL, auth_email TEXT,
+                permissions TEXT NOT NULL DEFAULT '{}', source TEXT NOT NULL DEFAULT 'manual',
+                failure_count INTEGER NOT NULL DEFAULT 0, last_used INTEGER NOT NULL
+             );
+             CREATE TABLE clone_checkpoints (
+                source TEXT NOT NULL, target TEXT NOT NULL, last_key TEXT NOT NULL,
+                copied INTEGER NOT NULL, updated_at INTEGER NOT NULL, PRIMARY KEY (source, target)
+             );"
+        ).unwrap();
+
+        Database::initialize_database(&conn).unwrap();
+        assert!(has_column(&conn, "clone_checkpoints", "target_title").unwrap());
+    }
+
+    #[test]
+    fn reads_cached_values_since_the_search_mark() {
+        let db = test_db();
+        for (key, synced_at) in [("old", 10), ("new", 20)] {
+            db.conn.execute(
+                "INSERT INTO remote_cache (account_id, namespace_id, kind, cache_key, payload, synced_at, expires_at)
+                 VALUES ('acc', 'ns', 'value', ?, '{}', ?, 0)",
+                params![key, synced_at],
+            ).unwrap();
+        }
+
+        assert_eq!(db.get_cached_value_keys().unwrap().len(), 2);
+        let since: Vec<String> = db.get_cached_values(15).unwrap().into_iter().map(|row| row.2).collect();
+        assert_eq!(since, vec!["new".to_string()]);
+
+        let source = remote("acc", "ns");
+        db.set_search_mark(&source.descriptor(), 20).unwrap();
+        assert_eq!(db.get_search_mark(&source.descriptor()).unwrap(), Some(20));
+        db.remove_search_source(&source).unwrap();
+        assert_eq!(db.get_search_mark(&source.descriptor()).unwrap(), None);
+    }
+}
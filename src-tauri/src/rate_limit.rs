@@ -0,0 +1,72 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::models::kv::ApiAuth;
+
+pub const REQUEST_LIMIT: usize = 1200;
+pub const WINDOW: Duration = Duration::from_secs(300);
+const BACKGROUND_RESERVE: usize = 200;
+
+static REQUESTS: Mutex<BTreeMap<u64, VecDeque<Instant>>> = Mutex::new(BTreeMap::new());
+
+pub struct Budget {
+    pub used: usize,
+    pub remaining: usize,
+    pub resets_in: Duration,
+}
+
+fn token_key(auth: &ApiAuth) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    match auth {
+        ApiAuth::Bearer { token } => token.hash(&mut hasher),
+        ApiAuth::GlobalKey { email, .. } => email.hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
+fn prune(requests: &mut VecDeque<Instant>, now: Instant) {
+    while requests.front().is_some_and(|sent| now.duration_since(*sent) >= WINDOW) {
+        requests.pop_front();
+    }
+}
+
+pub fn record(auth: &ApiAuth) {
+    let now = Instant::now();
+    let mut all_requests = REQUESTS.lock().unwrap();
+    let requests = all_requests.entry(token_key(auth)).or_default();
+    prune(requests, now);
+    requests.push_back(now);
+}
+
+pub fn budget(auth: &ApiAuth) -> Budget {
+    let now = Instant::now();
+    let mut all_requests = REQUESTS.lock().unwrap();
+    let requests = all_requests.entry(token_key(auth)).or_default();
+    prune(requests, now);
+
+    Budget {
+        used: requests.len(),
+        remaining: REQUEST_LIMIT.saturating_sub(requests.len()),
+        resets_in: requests
+            .front()
+            .map(|sent| WINDOW.saturating_sub(now.duration_since(*sent)))
+            .unwrap_or_default(),
+    }
+}
+
+/// Background work waits while the budget is down to the reserve kept free
+/// for interactive requests, so full listings and bulk jobs do not cause 429s.
+pub async fn throttle_background(auth: &ApiAuth) {
+    loop {
+        let budget = budget(auth);
+        if budget.remaining > BACKGROUND_RESERVE {
+            return;
+        }
+
+        let delay = budget.resets_in.max(Duration::from_millis(250));
+        tokio::time::sleep(delay).await;
+    }
+}
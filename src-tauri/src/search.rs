@@ -0,0 +1,90 @@
+use crate::models::search::SnippetPart;
+use crate::models::value::{KVValue, ValueKind};
+
+pub const HIGHLIGHT_START: &str = "\u{2}";
+pub const HIGHLIGHT_END: &str = "\u{3}";
+pub const SNIPPET_TOKENS: i64 = 16;
+const MAX_INDEXED_BYTES: usize = 1024 * 1024;
+
+pub fn indexable_text(value: &KVValue) -> String {
+    if !matches!(value.kind, ValueKind::Json | ValueKind::Text) {
+        return String::new();
+    }
+
+    let mut end = value.bytes.len().min(MAX_INDEXED_BYTES);
+    while end > 0 && end < value.bytes.len() && (value.bytes[end] & 0xC0) == 0x80 {
+        end -= 1;
+    }
+    String::from_utf8_lossy(&value.bytes[..end]).into_owned()
+}
+
+/// Quotes every term so ids like `user-123` are not parsed as query syntax.
+pub fn match_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+pub fn split_snippet(snippet: &str) -> Vec<SnippetPart> {
+    let mut parts = Vec::new();
+
+    for (index, piece) in snippet.split(HIGHLIGHT_START).enumerate() {
+        let (highlighted, rest) = match piece.split_once(HIGHLIGHT_END) {
+            Some((highlighted, rest)) if index > 0 => (Some(highlighted), rest),
+            _ => (None, piece),
+        };
+
+        if let Some(text) = highlighted.filter(|t| !t.is_empty()) {
+            parts.push(SnippetPart { text: text.to_string(), highlighted: true });
+        }
+        if !rest.is_empty() {
+            parts.push(SnippetPart { text: rest.to_string(), highlighted: false });
+        }
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(text: &str, highlighted: bool) -> SnippetPart {
+        SnippetPart { text: text.to_string(), highlighted }
+    }
+
+    #[test]
+    fn quotes_every_term() {
+        assert_eq!(match_query("user-123  NOT"), Some("\"user-123\" \"NOT\"".to_string()));
+        assert_eq!(match_query("say \"hi\""), Some("\"say\" \"\"\"hi\"\"\"".to_string()));
+        assert_eq!(match_query("   "), None);
+    }
+
+    #[test]
+    fn splits_highlighted_parts() {
+        let snippet = format!("a {s}match{e} and {s}another{e}", s = HIGHLIGHT_START, e = HIGHLIGHT_END);
+        assert_eq!(
+            split_snippet(&snippet),
+            vec![part("a ", false), part("match", true), part(" and ", false), part("another", true)]
+        );
+        assert_eq!(split_snippet("plain"), vec![part("plain", false)]);
+    }
+
+    #[test]
+    fn indexes_only_text_values() {
+        assert_eq!(indexable_text(&KVValue::from_bytes(b"{\"a\":1}".to_vec())), "{\"a\":1}");
+        assert_eq!(indexable_text(&KVValue::from_bytes(b"\x89PNG\r\n\x1a\n".to_vec())), "");
+    }
+
+    #[test]
+    fn cuts_large_values_on_a_char_boundary() {
+        let mut bytes = vec![b'a'; MAX_INDEXED_BYTES - 1];
+        bytes.extend("é".repeat(4).as_bytes());
+        let text = indexable_text(&KVValue::from_bytes(bytes));
+        assert_eq!(text.len(), MAX_INDEXED_BYTES - 1);
+        assert!(!text.contains('\u{FFFD}'));
+    }
+}
@@ -0,0 +1,71 @@
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use zeroize::Zeroizing;
+
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LENGTH: usize = 12;
+const SALT_LENGTH: usize = 16;
+
+#[derive(Clone)]
+pub struct TokenCipher {
+    cipher: Aes256Gcm,
+}
+
+impl TokenCipher {
+    pub fn derive(password: &str, salt: &[u8]) -> Result<Self, String> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::default()
+            .hash_password_into(password.as_bytes(), salt, key.as_mut())
+            .map_err(|e| format!("Failed to derive encryption key: {}", e))?;
+
+        Ok(TokenCipher {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref())),
+        })
+    }
+
+    pub fn generate_salt() -> Vec<u8> {
+        let mut salt = vec![0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        salt
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| "Failed to encrypt secret".to_string())?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let encoded = match stored.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(encoded) => encoded,
+            None => return Ok(stored.to_string()),
+        };
+
+        let payload = STANDARD.decode(encoded)
+            .map_err(|_| "Stored secret is corrupted".to_string())?;
+        if payload.len() < NONCE_LENGTH {
+            return Err("Stored secret is corrupted".to_string());
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+        let plaintext = self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "Failed to decrypt secret".to_string())?;
+
+        String::from_utf8(plaintext).map_err(|_| "Stored secret is corrupted".to_string())
+    }
+}
+
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
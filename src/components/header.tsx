@@ -1,6 +1,8 @@
 import { Button } from '@/components/ui/button'
 import { Separator } from '@/components/ui/separator'
+import { type RateLimitBudget, getRateLimitBudgets } from '@/lib/api'
 import { Cloud, Folder, RefreshCw } from 'lucide-react'
+import { useEffect, useState } from 'react'
 
 interface HeaderProps {
   title: string
@@ -23,6 +25,25 @@ export function Header({
   remoteConnections,
   isLoading,
 }: HeaderProps) {
+  const [budgets, setBudgets] = useState<RateLimitBudget[]>([])
+
+  useEffect(() => {
+    if (remoteConnections === 0) {
+      setBudgets([])
+      return
+    }
+
+    const refresh = () => getRateLimitBudgets().then(setBudgets).catch(() => setBudgets([]))
+    refresh()
+    const interval = setInterval(refresh, 5000)
+    return () => clearInterval(interval)
+  }, [remoteConnections])
+
+  const lowestBudget = budgets.reduce<RateLimitBudget | null>(
+    (lowest, budget) => (!lowest || budget.remaining < lowest.remaining ? budget : lowest),
+    null
+  )
+
   return (
     <header className="flex flex-col border-b border-zinc-800">
       <div className="flex h-14 items-center px-4 lg:px-6">
@@ -97,6 +118,14 @@ export function Header({
       <div className="flex px-4 text-xs text-zinc-500">
         <div className="flex-1">WRANGLER KV EXPLORER</div>
         <div className="flex items-center gap-2">
+          {lowestBudget && (
+            <span
+              className={lowestBudget.remaining < lowestBudget.limit * 0.2 ? 'text-amber-500' : ''}
+              title={`${lowestBudget.label}: ${lowestBudget.used} requests in the last ${lowestBudget.window_seconds / 60} minutes`}
+            >
+              API budget: {lowestBudget.remaining}/{lowestBudget.limit}
+            </span>
+          )}
           <span>
             Last updated: {new Date().toLocaleDateString()}, {new Date().toLocaleTimeString()}
           </span>
@@ -1,5 +1,6 @@
 import { Header } from '@/components/header'
 import { KeyValueTable } from '@/components/key-value-table'
+import { MasterPasswordModal } from '@/components/master-password-modal'
 import { NamespaceSidebar } from '@/components/namespace-sidebar'
 import { RemoteConnectionModal } from '@/components/remote-connection-modal'
 import { ResizableHandle, ResizablePanel, ResizablePanelGroup } from '@/components/ui/resizable'
@@ -10,17 +11,25 @@ import {
   type KVEntry,
   type KVNamespace,
   type LocalFolder,
+  type ValueKind,
   addFolder,
   connectCloudflare,
+  createMasterPassword,
+  decodeKVValue,
   deleteKeys,
   deleteRemoteKeys,
+  errorMessage,
   formatExpiration,
   getFolders,
   getRemoteKeys,
   getRemoteNamespaces,
   getRemoteValue,
+  getVaultStatus,
+  isApiError,
   loadFolder,
+  openRemoteValue,
   removeFolder,
+  unlockVault,
   updateRemoteValue,
   updateValue,
 } from '@/lib/api'
@@ -38,16 +47,22 @@ export function KVExplorer() {
   const [isEditing, setIsEditing] = useState(false)
   const [editingKey, setEditingKey] = useState<string | null>(null)
   const [editingValue, setEditingValue] = useState<unknown | null>(null)
+  const [editingFingerprint, setEditingFingerprint] = useState<string | null>(null)
+  const [editingKind, setEditingKind] = useState<ValueKind | undefined>(undefined)
   const [keyValues, setKeyValues] = useState<KVEntry[]>([])
   const [isRemoteModalOpen, setIsRemoteModalOpen] = useState(false)
   const [remoteConnections, setRemoteConnections] = useState<{ accountId: string }[]>([])
   const [nextCursor, setNextCursor] = useState<string | null>(null)
   const [isLoadingMore, setIsLoadingMore] = useState(false)
   const [totalKeys, setTotalKeys] = useState<number>(0)
+  const [isVaultModalOpen, setIsVaultModalOpen] = useState(false)
+  const [isNewVault, setIsNewVault] = useState(false)
+  const [isVaultRequired, setIsVaultRequired] = useState(false)
   const { toast } = useToast()
 
   useEffect(() => {
     loadFolders()
+    checkVault()
   }, [])
 
   useEffect(() => {
@@ -70,7 +85,48 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
+        variant: 'destructive',
+      })
+    } finally {
+      setIsLoading(false)
+    }
+  }
+
+  const checkVault = async () => {
+    try {
+      const status = await getVaultStatus()
+      if (!status.unlocked) {
+        setIsNewVault(!status.configured)
+        setIsVaultRequired(status.required)
+        setIsVaultModalOpen(true)
+      }
+    } catch (error) {
+      toast({
+        title: 'ERROR',
+        description: errorMessage(error),
+        variant: 'destructive',
+      })
+    }
+  }
+
+  const handleVaultSubmit = async (password: string) => {
+    try {
+      setIsLoading(true)
+      if (isNewVault) {
+        await createMasterPassword(password)
+      } else {
+        await unlockVault(password)
+      }
+
+      setIsVaultModalOpen(false)
+      setIsVaultRequired(false)
+
+      await loadRemoteNamespaces()
+    } catch (error) {
+      toast({
+        title: 'ERROR',
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -97,7 +153,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -132,7 +188,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -169,7 +225,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -199,7 +255,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -210,7 +266,7 @@ export function KVExplorer() {
   const loadRemoteNamespaces = async () => {
     try {
       setIsLoading(true)
-      const remoteNamespaces = await getRemoteNamespaces()
+      const { namespaces: remoteNamespaces, errors } = await getRemoteNamespaces()
 
       setNamespaces(prev => {
         const local = prev.filter(ns => ns.type === 'local')
@@ -220,10 +276,18 @@ export function KVExplorer() {
         }))
         return [...local, ...typed]
       })
+
+      for (const failure of errors) {
+        toast({
+          title: failure.needs_reauth ? `RE-AUTHENTICATE ${failure.label}` : `ERROR: ${failure.label}`,
+          description: failure.error.message,
+          variant: 'destructive',
+        })
+      }
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -257,7 +321,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -283,7 +347,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -332,7 +396,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -352,6 +416,8 @@ export function KVExplorer() {
     const loadValue = async () => {
       try {
         let value: unknown
+        let fingerprint: string | null = null
+        let kind: ValueKind | undefined
 
         if (selected.type === 'local') {
           value = keyValue.value
@@ -359,16 +425,21 @@ export function KVExplorer() {
           const accountId = selected.accountId || remoteConnections[0]?.accountId
           if (!accountId) throw new Error('Account ID not found')
 
-          value = await getRemoteValue(accountId, selected.id, keyValue.key)
+          const snapshot = await openRemoteValue(accountId, selected.id, keyValue.key)
+          value = decodeKVValue(snapshot.value)
+          fingerprint = snapshot.fingerprint
+          kind = snapshot.value.kind
         }
 
         setEditingKey(keyValue.key)
         setEditingValue(value)
+        setEditingFingerprint(fingerprint)
+        setEditingKind(kind)
         setIsEditing(true)
       } catch (error) {
         toast({
           title: 'ERROR',
-          description: String(error),
+          description: errorMessage(error),
           variant: 'destructive',
         })
       } finally {
@@ -437,7 +508,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -478,7 +549,26 @@ export function KVExplorer() {
         const accountId = selected.accountId || remoteConnections[0]?.accountId
         if (!accountId) throw new Error('Account ID not found')
 
-        await updateRemoteValue(accountId, selectedNamespace, editingKey, editingValue)
+        try {
+          await updateRemoteValue(accountId, selectedNamespace, editingKey, editingValue, {
+            expectedFingerprint: editingFingerprint ?? undefined,
+            kind: editingKind,
+          })
+        } catch (error) {
+          if (!isApiError(error) || error.kind !== 'conflict') throw error
+
+          const overwrite = window.confirm(
+            `${error.message}\n\nRemote value:\n${
+              error.remote ? JSON.stringify(decodeKVValue(error.remote.value), null, 2) : '(deleted)'
+            }\n\nOverwrite it with your changes?`
+          )
+          if (!overwrite) return
+
+          await updateRemoteValue(accountId, selectedNamespace, editingKey, editingValue, {
+            force: true,
+            kind: editingKind,
+          })
+        }
 
         if (viewingKeyId) {
           setSelectedValue(editingValue)
@@ -493,6 +583,8 @@ export function KVExplorer() {
       setIsEditing(false)
       setEditingKey(null)
       setEditingValue(null)
+      setEditingFingerprint(null)
+      setEditingKind(undefined)
 
       toast({
         title: 'VALUE UPDATED',
@@ -501,7 +593,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -536,7 +628,7 @@ export function KVExplorer() {
     } catch (error) {
       toast({
         title: 'ERROR',
-        description: String(error),
+        description: errorMessage(error),
         variant: 'destructive',
       })
     } finally {
@@ -548,6 +640,8 @@ export function KVExplorer() {
     setIsEditing(false)
     setEditingKey(null)
     setEditingValue(null)
+    setEditingFingerprint(null)
+    setEditingKind(undefined)
   }
 
   return (
@@ -629,6 +723,14 @@ export function KVExplorer() {
         onClose={() => setIsRemoteModalOpen(false)}
         onSave={handleRemoteConnectionSave}
       />
+
+      <MasterPasswordModal
+        isOpen={isVaultModalOpen}
+        isNewVault={isNewVault}
+        isRequired={isVaultRequired}
+        onClose={() => setIsVaultModalOpen(false)}
+        onSubmit={handleVaultSubmit}
+      />
     </>
   )
 }
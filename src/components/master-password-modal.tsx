@@ -0,0 +1,123 @@
+import { Button } from '@/components/ui/button'
+import {
+  Dialog,
+  DialogContent,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Lock } from 'lucide-react'
+import type React from 'react'
+import { useState } from 'react'
+
+interface MasterPasswordModalProps {
+  isOpen: boolean
+  isNewVault: boolean
+  isRequired?: boolean
+  onClose: () => void
+  onSubmit: (password: string) => void
+}
+
+export function MasterPasswordModal({
+  isOpen,
+  isNewVault,
+  isRequired = false,
+  onClose,
+  onSubmit,
+}: MasterPasswordModalProps) {
+  const [password, setPassword] = useState('')
+  const [confirmation, setConfirmation] = useState('')
+
+  const isValid = password.length >= 8 && (!isNewVault || password === confirmation)
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault()
+    if (isValid) {
+      onSubmit(password)
+      setPassword('')
+      setConfirmation('')
+    }
+  }
+
+  return (
+    <Dialog open={isOpen} onOpenChange={open => !open && !isRequired && onClose()}>
+      <DialogContent
+        className="border-zinc-800 bg-black font-mono text-white sm:max-w-md"
+        showCloseButton={!isRequired}
+      >
+        <DialogHeader>
+          <DialogTitle className="flex items-center gap-2 text-lg font-bold tracking-wider">
+            <Lock className="h-5 w-5 text-cyan-500" />
+            {isNewVault ? 'SET MASTER PASSWORD' : 'UNLOCK REMOTE CONNECTIONS'}
+          </DialogTitle>
+        </DialogHeader>
+        <form onSubmit={handleSubmit}>
+          <div className="grid gap-6 py-4">
+            <div className="grid gap-2">
+              <Label
+                htmlFor="masterPassword"
+                className="text-xs font-bold tracking-wider text-zinc-400"
+              >
+                MASTER PASSWORD
+              </Label>
+              <Input
+                id="masterPassword"
+                type="password"
+                value={password}
+                onChange={e => setPassword(e.target.value)}
+                className="border-zinc-800 bg-zinc-900 font-mono text-sm focus-visible:ring-zinc-700"
+                placeholder="Enter your master password"
+                required
+              />
+            </div>
+            {isNewVault && (
+              <div className="grid gap-2">
+                <Label
+                  htmlFor="masterPasswordConfirmation"
+                  className="text-xs font-bold tracking-wider text-zinc-400"
+                >
+                  CONFIRM PASSWORD
+                </Label>
+                <Input
+                  id="masterPasswordConfirmation"
+                  type="password"
+                  value={confirmation}
+                  onChange={e => setConfirmation(e.target.value)}
+                  className="border-zinc-800 bg-zinc-900 font-mono text-sm focus-visible:ring-zinc-700"
+                  placeholder="Repeat your master password"
+                  required
+                />
+                <p className="text-xs text-zinc-500">
+                  {isRequired
+                    ? 'Saved API tokens are stored unencrypted. Set a master password to encrypt them.'
+                    : 'Encrypts saved API tokens. At least 8 characters.'}
+                </p>
+              </div>
+            )}
+          </div>
+          <DialogFooter className="border-t border-zinc-800 pt-4">
+            {!isRequired && (
+              <Button
+                type="button"
+                variant="outline"
+                onClick={onClose}
+                className="border-zinc-700 bg-transparent hover:bg-zinc-900 cursor-pointer"
+              >
+                CANCEL
+              </Button>
+            )}
+            <Button
+              type="submit"
+              disabled={!isValid}
+              className="bg-cyan-700 hover:bg-cyan-600 cursor-pointer"
+            >
+              {isNewVault ? 'SAVE' : 'UNLOCK'}
+            </Button>
+          </DialogFooter>
+        </form>
+      </DialogContent>
+    </Dialog>
+  )
+}
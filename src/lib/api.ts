@@ -1,4 +1,5 @@
-import { open } from '@tauri-apps/api/dialog'
+import { open, save } from '@tauri-apps/api/dialog'
+import { type UnlistenFn, listen } from '@tauri-apps/api/event'
 // src/lib/api.ts
 import { invoke } from '@tauri-apps/api/tauri'
 
@@ -19,6 +20,354 @@ export interface KVNamespace {
   type: string
   accountId?: string
   folderId?: number
+  synced_at?: number | null
+}
+
+export type ValueKind = 'json' | 'text' | 'binary' | 'opaque'
+
+export interface KVValue {
+  kind: ValueKind
+  mime_type: string | null
+  base64: string
+  size: number
+}
+
+export interface RemoteValueSnapshot {
+  value: KVValue
+  metadata: unknown
+  fingerprint: string
+}
+
+export interface TokenPermissions {
+  read: boolean | null
+  write: boolean | null
+  manage_namespaces: boolean | null
+}
+
+export interface RemoteConnectionInfo {
+  id: number
+  account_id: string
+  account_name: string | null
+  label: string
+  permissions: TokenPermissions
+  source: 'manual' | 'wrangler' | 'environment'
+  auth_scheme: 'bearer' | 'global_key'
+  needs_reauth: boolean
+  permissions_unknown: boolean
+}
+
+export interface CloudflareAccount {
+  id: string
+  name: string
+}
+
+export interface RateLimitBudget {
+  account_id: string
+  label: string
+  limit: number
+  used: number
+  remaining: number
+  window_seconds: number
+  resets_in_seconds: number
+}
+
+export interface VaultStatus {
+  configured: boolean
+  unlocked: boolean
+  required: boolean
+}
+
+export interface AccountError {
+  account_id: string
+  label: string
+  error: ApiError
+  needs_reauth: boolean
+}
+
+export interface RemoteNamespaces {
+  namespaces: KVNamespace[]
+  errors: AccountError[]
+}
+
+export interface ExternalConnections {
+  connections: RemoteConnectionInfo[]
+  errors: AccountError[]
+}
+
+export interface CacheStatus {
+  offline: boolean
+  entries: number
+  last_synced: number | null
+}
+
+export interface RemoteKeysPage {
+  entries: KVEntry[]
+  cursor?: string
+  total: number
+  synced_at: number | null
+  offline: boolean
+}
+
+export type ApiErrorKind =
+  | 'key_not_found'
+  | 'namespace_not_found'
+  | 'namespace_exists'
+  | 'invalid_auth'
+  | 'permission_denied'
+  | 'permission_unknown'
+  | 'rate_limited'
+  | 'value_too_large'
+  | 'key_too_long'
+  | 'conflict'
+  | 'invalid_request'
+  | 'network_unavailable'
+  | 'server_error'
+  | 'other'
+
+export interface ApiError {
+  kind: ApiErrorKind
+  message: string
+  code?: number | null
+  status?: number | null
+  retry_after?: number | null
+  remote?: RemoteValueSnapshot | null
+  local?: KVValue
+}
+
+export function isApiError(error: unknown): error is ApiError {
+  return typeof error === 'object' && error !== null && 'kind' in error && 'message' in error
+}
+
+export function errorMessage(error: unknown): string {
+  return isApiError(error) ? error.message : String(error)
+}
+
+export type JobStatus = 'running' | 'completed' | 'failed' | 'cancelled'
+
+export interface JobProgress {
+  completed: number
+  total: number | null
+  message: string | null
+}
+
+export interface JobInfo {
+  id: number
+  kind: string
+  status: JobStatus
+  progress: JobProgress
+  result: unknown
+  error: unknown
+  started_at: number
+  finished_at: number | null
+}
+
+export interface JobProgressEvent extends JobProgress {
+  job_id: number
+}
+
+export type WriteMode = 'overwrite' | 'skip_existing'
+
+export type WriteAction = 'create' | 'overwrite' | 'skip' | 'expired' | 'delete'
+
+export interface TransferOptions {
+  mode: WriteMode
+  dry_run?: boolean
+}
+
+export interface DirectoryImportOptions extends TransferOptions {
+  prune?: boolean
+}
+
+export interface PlannedWrite {
+  key: string
+  action: WriteAction
+}
+
+export interface TransferSummary {
+  total: number
+  written: number
+  skipped: number
+  expired: number
+  deleted: number
+  dry_run: boolean
+  plan: PlannedWrite[]
+}
+
+export type NamespaceRef =
+  | { type: 'local'; folder_id: number; namespace_id: string }
+  | { type: 'remote'; account_id: string; namespace_id: string }
+
+export type DiffStatus = 'only_left' | 'only_right' | 'changed'
+
+export interface JsonChange {
+  path: string
+  kind: 'added' | 'removed' | 'changed'
+  left: unknown | null
+  right: unknown | null
+}
+
+export interface KeyDiff {
+  key: string
+  status: DiffStatus
+  value_changed: boolean
+  metadata_changed: boolean
+  expiration_changed: boolean
+  left_expiration: number | null
+  right_expiration: number | null
+  left_metadata: unknown | null
+  right_metadata: unknown | null
+  json_changes: JsonChange[]
+  json_changes_truncated: boolean
+}
+
+export interface DiffSummary {
+  only_left: number
+  only_right: number
+  changed: number
+  identical: number
+}
+
+interface DiffBatchEvent {
+  job_id: number
+  diffs: KeyDiff[]
+}
+
+export type SyncPolicy = 'prefer_left' | 'prefer_right' | 'newest_wins' | 'ask'
+
+export type SyncAction = 'copy_to_right' | 'copy_to_left' | 'delete_right' | 'delete_left' | 'conflict'
+
+export interface SyncChange {
+  key: string
+  action: SyncAction
+  conflict: boolean
+  left_fingerprint: string | null
+  right_fingerprint: string | null
+}
+
+export interface SyncPlan {
+  left: NamespaceRef
+  right: NamespaceRef
+  policy: SyncPolicy
+  changes: SyncChange[]
+  in_sync: number
+  has_baseline: boolean
+}
+
+export interface SyncResult {
+  copied_to_left: number
+  copied_to_right: number
+  deleted_left: number
+  deleted_right: number
+  unresolved: string[]
+  stale: string[]
+}
+
+export interface CloneTarget {
+  account_id: string
+  namespace_id?: string | null
+  title?: string | null
+}
+
+export interface CloneCheckpoint {
+  source: NamespaceRef
+  target: NamespaceRef
+  target_title: string | null
+  last_key: string
+  copied: number
+  updated_at: number
+}
+
+export interface CloneResult {
+  namespace_id: string
+  copied: number
+  expired: number
+  resumed_from: string | null
+}
+
+export interface ExportFilter {
+  prefix?: string | null
+  keys?: string[] | null
+}
+
+export interface ExportSummary {
+  path: string
+  exported: number
+  expired: number
+}
+
+export type ImportFormat = 'bulk_json' | 'ndjson' | 'csv'
+
+export interface CsvColumns {
+  key: string
+  value: string
+  metadata?: string | null
+  expiration?: string | null
+}
+
+export interface ImportOptions {
+  format: ImportFormat
+  csv_columns?: CsvColumns | null
+  mode: WriteMode
+  dry_run?: boolean
+}
+
+export interface ImportIssue {
+  line: number
+  file?: string
+  key: string | null
+  message: string
+}
+
+export interface ImportSummary extends TransferSummary {
+  issues: ImportIssue[]
+}
+
+export interface NdjsonExportOptions {
+  filter?: ExportFilter
+  gzip?: boolean
+}
+
+export interface NdjsonExportSummary {
+  path: string
+  namespace: NamespaceRef
+  exported: number
+  expired: number
+  gzip: boolean
+  resumed: boolean
+  completed_at: number
+}
+
+export interface SearchOptions {
+  limit?: number
+  refresh?: boolean
+}
+
+export interface SnippetPart {
+  text: string
+  highlighted: boolean
+}
+
+export interface SearchHit {
+  namespace: NamespaceRef
+  key: string
+  snippet: SnippetPart[]
+}
+
+export interface SearchResults {
+  hits: SearchHit[]
+  reindexed: number
+}
+
+export type MaskStrategy = 'remove' | 'redact' | 'hash'
+
+export interface MaskRule {
+  field: string
+  strategy: MaskStrategy
+}
+
+export interface PullOptions {
+  prefix?: string | null
+  masks?: MaskRule[]
 }
 
 export interface LocalFolder {
@@ -27,6 +376,76 @@ export interface LocalFolder {
   name: string
 }
 
+export async function getVaultStatus(): Promise<VaultStatus> {
+  return invoke<VaultStatus>('get_vault_status')
+}
+
+export async function createMasterPassword(password: string): Promise<void> {
+  await invoke('create_master_password', { password })
+}
+
+export async function unlockVault(password: string): Promise<void> {
+  await invoke('unlock_vault', { password })
+}
+
+export async function lockVault(): Promise<void> {
+  await invoke('lock_vault')
+}
+
+export async function changeMasterPassword(
+  currentPassword: string,
+  newPassword: string
+): Promise<void> {
+  await invoke('change_master_password', { currentPassword, newPassword })
+}
+
+export async function listJobs(): Promise<JobInfo[]> {
+  return invoke<JobInfo[]>('list_jobs')
+}
+
+export async function getJob(jobId: number): Promise<JobInfo> {
+  return invoke<JobInfo>('get_job', { jobId })
+}
+
+export async function cancelJob(jobId: number): Promise<void> {
+  await invoke('cancel_job', { jobId })
+}
+
+export async function onJobProgress(
+  handler: (progress: JobProgressEvent) => void
+): Promise<UnlistenFn> {
+  return listen<JobProgressEvent>('job-progress', event => handler(event.payload))
+}
+
+export async function waitForJob<T>(jobId: number): Promise<T> {
+  let unlisten: UnlistenFn | undefined
+
+  const finished = new Promise<JobInfo>((resolve, reject) => {
+    listen<JobInfo>('job-finished', event => {
+      if (event.payload.id === jobId) {
+        resolve(event.payload)
+      }
+    })
+      .then(stop => {
+        unlisten = stop
+        return getJob(jobId).then(job => {
+          if (job.status !== 'running') {
+            resolve(job)
+          }
+        })
+      })
+      .catch(reject)
+  })
+
+  const job = await finished.finally(() => unlisten?.())
+
+  if (job.status !== 'completed') {
+    throw job.error ?? `Job ${job.status}`
+  }
+
+  return job.result as T
+}
+
 export async function getFolders(): Promise<LocalFolder[]> {
   return invoke<LocalFolder[]>('get_folders')
 }
@@ -43,7 +462,8 @@ export async function addFolder(): Promise<{ folderId: number; namespaces: KVNam
   }
 
   try {
-    const result = await invoke<KVNamespace[]>('add_folder', { path: selected })
+    const jobId = await invoke<number>('add_folder', { path: selected })
+    const result = await waitForJob<KVNamespace[]>(jobId)
 
     const folderId = result.length > 0 ? result[0].folderId || 0 : 0
 
@@ -62,7 +482,8 @@ export async function removeFolder(folderId: number): Promise<void> {
 }
 
 export async function loadFolder(folderId: number): Promise<KVNamespace[]> {
-  return invoke<KVNamespace[]>('load_folder', { folderId })
+  const jobId = await invoke<number>('load_folder', { folderId })
+  return waitForJob<KVNamespace[]>(jobId)
 }
 
 export async function deleteKeys(
@@ -91,36 +512,242 @@ export async function updateValue(
   })
 }
 
-export async function connectCloudflare(accountId: string, apiToken: string): Promise<void> {
-  await invoke('connect_cloudflare', { accountId, apiToken })
+export async function connectCloudflare(
+  accountId: string,
+  apiToken: string,
+  options: { label?: string; authEmail?: string } = {}
+): Promise<RemoteConnectionInfo> {
+  return invoke<RemoteConnectionInfo>('connect_cloudflare', {
+    accountId,
+    apiToken,
+    authEmail: options.authEmail,
+    label: options.label,
+  })
+}
+
+export async function discoverCloudflareAccounts(
+  apiToken: string,
+  authEmail?: string
+): Promise<CloudflareAccount[]> {
+  return invoke<CloudflareAccount[]>('discover_cloudflare_accounts', { apiToken, authEmail })
+}
+
+export async function connectCloudflareAccounts(
+  apiToken: string,
+  accountIds: string[],
+  authEmail?: string
+): Promise<RemoteConnectionInfo[]> {
+  return invoke<RemoteConnectionInfo[]>('connect_cloudflare_accounts', {
+    apiToken,
+    authEmail,
+    accountIds,
+  })
+}
+
+export async function loadExternalConnections(): Promise<ExternalConnections> {
+  return invoke<ExternalConnections>('load_external_connections')
+}
+
+export async function listRemoteConnections(): Promise<RemoteConnectionInfo[]> {
+  return invoke<RemoteConnectionInfo[]>('list_remote_connections')
+}
+
+export async function renameRemoteConnection(
+  connectionId: number,
+  label: string
+): Promise<RemoteConnectionInfo> {
+  return invoke<RemoteConnectionInfo>('rename_remote_connection', { connectionId, label })
+}
+
+export async function replaceRemoteConnectionToken(
+  connectionId: number,
+  apiToken: string,
+  authEmail?: string
+): Promise<void> {
+  await invoke('replace_remote_connection_token', { connectionId, apiToken, authEmail })
+}
+
+export async function disconnectRemoteConnection(connectionId: number): Promise<void> {
+  await invoke('disconnect_remote_connection', { connectionId })
+}
+
+export async function getRateLimitBudgets(): Promise<RateLimitBudget[]> {
+  return invoke<RateLimitBudget[]>('get_rate_limit_budgets')
 }
 
-export async function getRemoteNamespaces(): Promise<KVNamespace[]> {
-  return invoke<KVNamespace[]>('get_remote_namespaces')
+export async function getRemoteNamespaces(refresh = false): Promise<RemoteNamespaces> {
+  return invoke<RemoteNamespaces>('get_remote_namespaces', { refresh })
 }
 
 export async function getRemoteKeys(
   accountId: string,
   namespaceId: string,
-  cursor?: string
-): Promise<{ entries: KVEntry[], cursor?: string, total: number }> {
-  const result = await invoke<{ entries: KVEntry[], cursor?: string, total: number }>(
+  cursor?: string,
+  refresh = false
+): Promise<RemoteKeysPage> {
+  const result = await invoke<RemoteKeysPage>(
     'get_remote_keys',
-    { accountId, namespaceId, cursor }
+    { accountId, namespaceId, cursor, refresh }
   );
 
   return result;
 }
 
+export async function getRemoteCacheStatus(): Promise<CacheStatus> {
+  return invoke<CacheStatus>('get_remote_cache_status')
+}
+
+export async function clearRemoteCache(accountId?: string): Promise<void> {
+  await invoke('clear_remote_cache', { accountId })
+}
+
+export function isKVValue(value: unknown): value is KVValue {
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    'kind' in value &&
+    'base64' in value
+  )
+}
+
+function encodeBase64(text: string): string {
+  const bytes = new TextEncoder().encode(text)
+  let binary = ''
+  for (const byte of bytes) {
+    binary += String.fromCharCode(byte)
+  }
+  return btoa(binary)
+}
+
+function decodeBase64(encoded: string): string {
+  const binary = atob(encoded)
+  const bytes = new Uint8Array(binary.length)
+  for (let i = 0; i < binary.length; i++) {
+    bytes[i] = binary.charCodeAt(i)
+  }
+  return new TextDecoder().decode(bytes)
+}
+
+export function decodeKVValue(value: KVValue): unknown {
+  switch (value.kind) {
+    case 'json':
+      return JSON.parse(decodeBase64(value.base64))
+    case 'text':
+      return decodeBase64(value.base64)
+    default:
+      return value
+  }
+}
+
+export function encodeKVValue(value: unknown, kind?: ValueKind): KVValue {
+  if (isKVValue(value)) {
+    return value
+  }
+
+  // A string that was decoded from a JSON value is written back as a JSON string literal.
+  if (typeof value === 'string' && kind !== 'json') {
+    return {
+      kind: 'text',
+      mime_type: 'text/plain',
+      base64: encodeBase64(value),
+      size: new TextEncoder().encode(value).length,
+    }
+  }
+
+  const text = JSON.stringify(value)
+  return {
+    kind: 'json',
+    mime_type: 'application/json',
+    base64: encodeBase64(text),
+    size: new TextEncoder().encode(text).length,
+  }
+}
+
+export async function getRawRemoteValue(
+  accountId: string,
+  namespaceId: string,
+  keyName: string,
+  refresh = false
+): Promise<KVValue> {
+  return invoke<KVValue>('get_remote_value', {
+    accountId,
+    namespaceId,
+    keyName,
+    refresh,
+  })
+}
+
 export async function getRemoteValue(
+  accountId: string,
+  namespaceId: string,
+  keyName: string,
+  refresh = false
+): Promise<unknown> {
+  const value = await getRawRemoteValue(accountId, namespaceId, keyName, refresh)
+  return decodeKVValue(value)
+}
+
+export interface BulkValueResult {
+  value: KVValue | null
+  error: ApiError | null
+}
+
+export async function getRemoteValues(
+  accountId: string,
+  namespaceId: string,
+  keys: string[],
+  refresh = false
+): Promise<Record<string, BulkValueResult>> {
+  return invoke<Record<string, BulkValueResult>>('get_remote_values', {
+    accountId,
+    namespaceId,
+    keys,
+    refresh,
+  })
+}
+
+export async function openRemoteValue(
   accountId: string,
   namespaceId: string,
   keyName: string
+): Promise<RemoteValueSnapshot> {
+  return invoke<RemoteValueSnapshot>('open_remote_value', {
+    accountId,
+    namespaceId,
+    keyName,
+  })
+}
+
+export async function getRemoteMetadata(
+  accountId: string,
+  namespaceId: string,
+  keyName: string,
+  refresh = false
 ): Promise<unknown> {
-  return invoke('get_remote_value', {
+  return invoke('get_remote_metadata', {
     accountId,
     namespaceId,
     keyName,
+    refresh,
+  })
+}
+
+export interface BulkMetadataResult {
+  metadata: unknown
+  error: ApiError | null
+}
+
+export async function getRemoteKeysMetadata(
+  accountId: string,
+  namespaceId: string,
+  keys: string[],
+  refresh = false
+): Promise<Record<string, BulkMetadataResult>> {
+  return invoke<Record<string, BulkMetadataResult>>('get_remote_keys_metadata', {
+    accountId,
+    namespaceId,
+    keys,
+    refresh,
   })
 }
 
@@ -128,15 +755,28 @@ export async function updateRemoteValue(
   accountId: string,
   namespaceId: string,
   keyName: string,
-  value: unknown
+  value: unknown,
+  options: { expectedFingerprint?: string; force?: boolean; kind?: ValueKind } = {}
 ): Promise<void> {
-  const valueStr = JSON.stringify(value)
-
   await invoke('update_remote_kv', {
     accountId,
     namespaceId,
     keyName,
-    value: valueStr,
+    value: encodeKVValue(value, options.kind),
+    expectedFingerprint: options.expectedFingerprint,
+    force: options.force,
+  })
+}
+
+export async function startDeleteRemoteKeys(
+  accountId: string,
+  namespaceId: string,
+  keys: string[]
+): Promise<number> {
+  return invoke<number>('delete_remote_kv', {
+    accountId,
+    namespaceId,
+    keys,
   })
 }
 
@@ -145,13 +785,253 @@ export async function deleteRemoteKeys(
   namespaceId: string,
   keys: string[]
 ): Promise<void> {
-  await invoke('delete_remote_kv', {
+  const jobId = await startDeleteRemoteKeys(accountId, namespaceId, keys)
+  await waitForJob<number>(jobId)
+}
+
+export async function startListRemoteKeys(accountId: string, namespaceId: string): Promise<number> {
+  return invoke<number>('list_remote_keys', { accountId, namespaceId })
+}
+
+export async function listAllRemoteKeys(accountId: string, namespaceId: string): Promise<KVEntry[]> {
+  const jobId = await startListRemoteKeys(accountId, namespaceId)
+  return waitForJob<KVEntry[]>(jobId)
+}
+
+export async function startCopyLocalToRemote(
+  folderId: number,
+  namespaceId: string,
+  accountId: string,
+  targetNamespaceId: string,
+  options: TransferOptions
+): Promise<number> {
+  return invoke<number>('copy_local_to_remote', {
+    folderId,
+    namespaceId,
+    accountId,
+    targetNamespaceId,
+    options,
+  })
+}
+
+export async function copyLocalToRemote(
+  folderId: number,
+  namespaceId: string,
+  accountId: string,
+  targetNamespaceId: string,
+  options: TransferOptions
+): Promise<TransferSummary> {
+  const jobId = await startCopyLocalToRemote(folderId, namespaceId, accountId, targetNamespaceId, options)
+  return waitForJob<TransferSummary>(jobId)
+}
+
+export async function startPullRemoteToLocal(
+  accountId: string,
+  namespaceId: string,
+  folderId: number,
+  targetNamespaceId: string,
+  options: PullOptions = {}
+): Promise<number> {
+  return invoke<number>('pull_remote_to_local', {
     accountId,
     namespaceId,
-    keys,
+    folderId,
+    targetNamespaceId,
+    options,
+  })
+}
+
+export async function pullRemoteToLocal(
+  accountId: string,
+  namespaceId: string,
+  folderId: number,
+  targetNamespaceId: string,
+  options: PullOptions = {}
+): Promise<TransferSummary> {
+  const jobId = await startPullRemoteToLocal(accountId, namespaceId, folderId, targetNamespaceId, options)
+  return waitForJob<TransferSummary>(jobId)
+}
+
+export async function diffNamespaces(
+  left: NamespaceRef,
+  right: NamespaceRef,
+  onDiffs: (diffs: KeyDiff[]) => void
+): Promise<DiffSummary> {
+  // Batches can arrive before the job id is known, so buffer them until then.
+  let jobId: number | undefined
+  const pending: DiffBatchEvent[] = []
+  const unlisten = await listen<DiffBatchEvent>('diff-batch', event => {
+    if (jobId === undefined) {
+      pending.push(event.payload)
+    } else if (event.payload.job_id === jobId) {
+      onDiffs(event.payload.diffs)
+    }
+  })
+
+  try {
+    jobId = await invoke<number>('diff_namespaces', { left, right })
+    for (const batch of pending) {
+      if (batch.job_id === jobId) {
+        onDiffs(batch.diffs)
+      }
+    }
+    return await waitForJob<DiffSummary>(jobId)
+  } finally {
+    unlisten()
+  }
+}
+
+export async function planSync(
+  left: NamespaceRef,
+  right: NamespaceRef,
+  policy: SyncPolicy
+): Promise<SyncPlan> {
+  const jobId = await invoke<number>('plan_sync', { left, right, policy })
+  return waitForJob<SyncPlan>(jobId)
+}
+
+export async function applySync(
+  plan: SyncPlan,
+  resolutions: Record<string, SyncAction> = {}
+): Promise<SyncResult> {
+  const jobId = await invoke<number>('apply_sync', { plan, resolutions })
+  return waitForJob<SyncResult>(jobId)
+}
+
+export async function startCloneNamespace(
+  sourceAccountId: string,
+  sourceNamespaceId: string,
+  target: CloneTarget,
+  resume = true
+): Promise<number> {
+  return invoke<number>('clone_namespace', {
+    sourceAccountId,
+    sourceNamespaceId,
+    target,
+    resume,
   })
 }
 
+export async function cloneNamespace(
+  sourceAccountId: string,
+  sourceNamespaceId: string,
+  target: CloneTarget,
+  resume = true
+): Promise<CloneResult> {
+  const jobId = await startCloneNamespace(sourceAccountId, sourceNamespaceId, target, resume)
+  return waitForJob<CloneResult>(jobId)
+}
+
+export async function listCloneCheckpoints(): Promise<CloneCheckpoint[]> {
+  return invoke<CloneCheckpoint[]>('list_clone_checkpoints')
+}
+
+export async function exportBulkJson(
+  namespace: NamespaceRef,
+  filter: ExportFilter = {}
+): Promise<ExportSummary | null> {
+  const path = await save({
+    title: 'Export Namespace',
+    defaultPath: `${namespace.namespace_id}.json`,
+    filters: [{ name: 'JSON', extensions: ['json'] }],
+  })
+
+  if (!path) {
+    return null
+  }
+
+  const jobId = await invoke<number>('export_bulk_json', { namespace, path, filter })
+  return waitForJob<ExportSummary>(jobId)
+}
+
+export async function importKeys(
+  namespace: NamespaceRef,
+  options: ImportOptions
+): Promise<ImportSummary | null> {
+  const extensions: Record<ImportFormat, string[]> = {
+    bulk_json: ['json'],
+    ndjson: ['ndjson', 'jsonl', 'gz'],
+    csv: ['csv'],
+  }
+  const path = await open({
+    multiple: false,
+    title: 'Import Keys',
+    filters: [{ name: options.format, extensions: extensions[options.format] }],
+  })
+
+  if (!path || Array.isArray(path)) {
+    return null
+  }
+
+  const jobId = await invoke<number>('import_keys', { namespace, path, options })
+  return waitForJob<ImportSummary>(jobId)
+}
+
+export async function startExportNdjson(
+  namespace: NamespaceRef,
+  path: string,
+  options: NdjsonExportOptions = {},
+  resume = true
+): Promise<number> {
+  return invoke<number>('export_ndjson', { namespace, path, options, resume })
+}
+
+export async function exportNdjson(
+  namespace: NamespaceRef,
+  options: NdjsonExportOptions = {}
+): Promise<NdjsonExportSummary | null> {
+  const extension = options.gzip ? 'ndjson.gz' : 'ndjson'
+  const path = await save({
+    title: 'Export Namespace',
+    defaultPath: `${namespace.namespace_id}.${extension}`,
+    filters: [{ name: 'NDJSON', extensions: [options.gzip ? 'gz' : 'ndjson'] }],
+  })
+
+  if (!path) {
+    return null
+  }
+
+  const jobId = await startExportNdjson(namespace, path, options)
+  return waitForJob<NdjsonExportSummary>(jobId)
+}
+
+export async function exportDirectory(
+  namespace: NamespaceRef,
+  filter: ExportFilter = {}
+): Promise<ExportSummary | null> {
+  const path = await open({ directory: true, title: 'Export Namespace to Folder' })
+
+  if (!path || Array.isArray(path)) {
+    return null
+  }
+
+  const jobId = await invoke<number>('export_directory', { namespace, path, filter })
+  return waitForJob<ExportSummary>(jobId)
+}
+
+export async function importDirectory(
+  namespace: NamespaceRef,
+  options: DirectoryImportOptions
+): Promise<ImportSummary | null> {
+  const path = await open({ directory: true, title: 'Import Namespace from Folder' })
+
+  if (!path || Array.isArray(path)) {
+    return null
+  }
+
+  const jobId = await invoke<number>('import_directory', { namespace, path, options })
+  return waitForJob<ImportSummary>(jobId)
+}
+
+export async function startSearchValues(query: string, options: SearchOptions = {}): Promise<number> {
+  return invoke<number>('search_values', { query, options })
+}
+
+export async function searchValues(query: string, options: SearchOptions = {}): Promise<SearchResults> {
+  const jobId = await startSearchValues(query, options)
+  return waitForJob<SearchResults>(jobId)
+}
+
 export function formatExpiration(timestamp: number | null): string {
   if (!timestamp) {
     return 'No expiration'